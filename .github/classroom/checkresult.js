@@ -4,11 +4,11 @@ function judge(outputFile) {
     try {
         let jsonResult = JSON.parse(outputFile);
         let points = {};
-        jsonResult.exercises.forEach(({ name, result }) => {
+        jsonResult.exercises.forEach(({ name, result, points: weight = 1 }) => {
             if (result) {
-                points[name] = [1,1]
+                points[name] = [weight, weight]
             } else {
-                points[name] = [0,1]
+                points[name] = [0, weight]
             }
         })
         return points;
@@ -0,0 +1,42 @@
+use super::*;
+
+fn list(values: &[i32]) -> LinkedList<i32> {
+    let mut list = LinkedList::new();
+    for &value in values {
+        list.add(value);
+    }
+    list
+}
+
+fn values(list: &mut LinkedList<i32>) -> ::std::vec::Vec<i32> {
+    (0..list.length as i32)
+        .map(|i| *list.get(i).unwrap())
+        .collect()
+}
+
+#[test]
+fn merges_interleaved_lists() {
+    let mut merged = LinkedList::merge(list(&[1, 4, 9]), list(&[2, 3, 10, 12]));
+    ::core::assert_eq!(merged.length, 7);
+    ::core::assert_eq!(values(&mut merged), [1, 2, 3, 4, 9, 10, 12]);
+}
+
+#[test]
+fn merges_lists_with_equal_values() {
+    let mut merged = LinkedList::merge(list(&[1, 2, 2]), list(&[2, 3]));
+    ::core::assert_eq!(values(&mut merged), [1, 2, 2, 2, 3]);
+}
+
+#[test]
+fn merges_empty_lists() {
+    let mut merged = LinkedList::merge(list(&[]), list(&[]));
+    ::core::assert_eq!(merged.length, 0);
+    ::core::assert_eq!(merged.get(0), None);
+}
+
+#[test]
+fn merged_list_can_grow() {
+    let mut merged = LinkedList::merge(list(&[5]), list(&[1]));
+    merged.add(7);
+    ::core::assert_eq!(values(&mut merged), [1, 5, 7]);
+}
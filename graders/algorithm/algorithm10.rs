@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn adds_edges_both_ways() {
+    let mut graph = UndirectedGraph::new();
+    graph.add_edge(("x", "y", 3));
+    let table = graph.adjacency_table();
+    ::core::assert_eq!(table["x"], [("y".to_string(), 3)]);
+    ::core::assert_eq!(table["y"], [("x".to_string(), 3)]);
+}
+
+#[test]
+fn adds_the_nodes_of_an_edge() {
+    let mut graph = UndirectedGraph::new();
+    graph.add_edge(("a", "b", 1));
+    graph.add_edge(("b", "c", 2));
+    ::core::assert!(graph.contains("a"));
+    ::core::assert!(graph.contains("c"));
+    ::core::assert!(!graph.contains("d"));
+    ::core::assert_eq!(graph.nodes().len(), 3);
+}
+
+#[test]
+fn lists_every_edge_once() {
+    let mut graph = UndirectedGraph::new();
+    graph.add_edge(("a", "b", 1));
+    graph.add_edge(("b", "c", 2));
+    graph.add_edge(("c", "a", 3));
+    let mut edges = graph.edges();
+    edges.sort();
+    let (a, b, c) = ("a".to_string(), "b".to_string(), "c".to_string());
+    ::core::assert_eq!(edges, [(&a, &b, 1), (&a, &c, 3), (&b, &c, 2)]);
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+fn list(values: &[i32]) -> LinkedList<i32> {
+    let mut list = LinkedList::new();
+    for &value in values {
+        list.add(value);
+    }
+    list
+}
+
+fn values(list: &mut LinkedList<i32>) -> ::std::vec::Vec<i32> {
+    (0..list.length as i32)
+        .map(|i| *list.get(i).unwrap())
+        .collect()
+}
+
+#[test]
+fn reverses_odd_length_list() {
+    let mut list = list(&[1, 2, 3, 4, 5]);
+    list.reverse();
+    ::core::assert_eq!(values(&mut list), [5, 4, 3, 2, 1]);
+}
+
+#[test]
+fn reverses_twice_back_to_the_original() {
+    let mut list = list(&[8, 6, 7]);
+    list.reverse();
+    list.reverse();
+    ::core::assert_eq!(values(&mut list), [8, 6, 7]);
+}
+
+#[test]
+fn reverses_short_lists() {
+    let mut empty = list(&[]);
+    empty.reverse();
+    ::core::assert_eq!(empty.get(0), None);
+
+    let mut single = list(&[42]);
+    single.reverse();
+    ::core::assert_eq!(values(&mut single), [42]);
+}
+
+#[test]
+fn reversed_list_can_grow() {
+    let mut list = list(&[1, 2]);
+    list.reverse();
+    list.add(3);
+    ::core::assert_eq!(values(&mut list), [2, 1, 3]);
+}
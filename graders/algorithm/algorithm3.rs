@@ -0,0 +1,45 @@
+use super::*;
+
+#[test]
+fn sorts_sorted_and_reversed_arrays() {
+    let mut sorted = [1, 2, 3, 4, 5, 6];
+    sort(&mut sorted);
+    ::core::assert_eq!(sorted, [1, 2, 3, 4, 5, 6]);
+
+    let mut reversed = [6, 5, 4, 3, 2, 1];
+    sort(&mut reversed);
+    ::core::assert_eq!(reversed, [1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn sorts_negative_numbers() {
+    let mut array = [3, -1, 0, -7, 2, -1];
+    sort(&mut array);
+    ::core::assert_eq!(array, [-7, -1, -1, 0, 2, 3]);
+}
+
+#[test]
+fn sorts_equal_values() {
+    let mut array = [4, 4, 4, 4];
+    sort(&mut array);
+    ::core::assert_eq!(array, [4, 4, 4, 4]);
+}
+
+#[test]
+fn sorts_other_types() {
+    let mut floats = [2.5, -0.5, 1.0];
+    sort(&mut floats);
+    ::core::assert_eq!(floats, [-0.5, 1.0, 2.5]);
+
+    let mut chars = ['r', 'u', 's', 't'];
+    sort(&mut chars);
+    ::core::assert_eq!(chars, ['r', 's', 't', 'u']);
+}
+
+#[test]
+fn sorts_large_array() {
+    let mut array: ::std::vec::Vec<i32> = (0..1000).map(|i| (i * 7919) % 1000).collect();
+    sort(&mut array);
+    let expected: ::std::vec::Vec<i32> = (0..1000).collect();
+    ::core::assert_eq!(array, expected);
+}
@@ -0,0 +1,38 @@
+use super::*;
+
+#[test]
+fn finds_inserted_values_only() {
+    let mut bst = BinarySearchTree::new();
+    for value in [50, 30, 70, 20, 40, 60, 80] {
+        bst.insert(value);
+    }
+    for value in [50, 30, 70, 20, 40, 60, 80] {
+        ::core::assert!(bst.search(value), "{value} should be found");
+    }
+    for value in [10, 35, 65, 90] {
+        ::core::assert!(!bst.search(value), "{value} shouldn't be found");
+    }
+}
+
+#[test]
+fn keeps_the_order_of_a_search_tree() {
+    let mut bst = BinarySearchTree::new();
+    for value in [5, 3, 8, 1, 4] {
+        bst.insert(value);
+    }
+    let root = bst.root.as_ref().unwrap();
+    ::core::assert_eq!(root.value, 5);
+    ::core::assert_eq!(root.left.as_ref().unwrap().value, 3);
+    ::core::assert_eq!(root.right.as_ref().unwrap().value, 8);
+    ::core::assert_eq!(root.left.as_ref().unwrap().right.as_ref().unwrap().value, 4);
+}
+
+#[test]
+fn searches_strings() {
+    let mut bst = BinarySearchTree::new();
+    bst.insert("m".to_string());
+    bst.insert("c".to_string());
+    bst.insert("x".to_string());
+    ::core::assert!(bst.search("c".to_string()));
+    ::core::assert!(!bst.search("d".to_string()));
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn visits_by_levels() {
+    let mut graph = Graph::new(7);
+    graph.add_edge(0, 1);
+    graph.add_edge(0, 2);
+    graph.add_edge(1, 3);
+    graph.add_edge(1, 4);
+    graph.add_edge(2, 5);
+    graph.add_edge(2, 6);
+    ::core::assert_eq!(graph.bfs_with_return(0), [0, 1, 2, 3, 4, 5, 6]);
+    ::core::assert_eq!(graph.bfs_with_return(3), [3, 1, 0, 4, 2, 5, 6]);
+}
+
+#[test]
+fn visits_only_the_reachable_nodes() {
+    let mut graph = Graph::new(5);
+    graph.add_edge(0, 1);
+    graph.add_edge(2, 3);
+    ::core::assert_eq!(graph.bfs_with_return(0), [0, 1]);
+    ::core::assert_eq!(graph.bfs_with_return(4), [4]);
+}
+
+#[test]
+fn visits_every_node_once() {
+    let mut graph = Graph::new(4);
+    for a in 0..4 {
+        for b in a + 1..4 {
+            graph.add_edge(a, b);
+        }
+    }
+    ::core::assert_eq!(graph.bfs_with_return(2), [2, 0, 1, 3]);
+}
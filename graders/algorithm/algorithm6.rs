@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn goes_deep_before_wide() {
+    let mut graph = Graph::new(6);
+    graph.add_edge(0, 1);
+    graph.add_edge(0, 2);
+    graph.add_edge(1, 3);
+    graph.add_edge(3, 4);
+    graph.add_edge(2, 5);
+    ::core::assert_eq!(graph.dfs(0), [0, 1, 3, 4, 2, 5]);
+}
+
+#[test]
+fn visits_every_node_once() {
+    let mut graph = Graph::new(4);
+    for a in 0..4 {
+        for b in a + 1..4 {
+            graph.add_edge(a, b);
+        }
+    }
+    ::core::assert_eq!(graph.dfs(3), [3, 0, 1, 2]);
+}
+
+#[test]
+fn visits_only_the_reachable_nodes() {
+    let mut graph = Graph::new(4);
+    graph.add_edge(1, 2);
+    ::core::assert_eq!(graph.dfs(0), [0]);
+    ::core::assert_eq!(graph.dfs(2), [2, 1]);
+}
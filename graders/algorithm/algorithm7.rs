@@ -0,0 +1,30 @@
+use super::*;
+
+#[test]
+fn matches_nested_brackets() {
+    ::core::assert!(bracket_match("fn main() { let v = vec![(1, 2)]; }"));
+    ::core::assert!(bracket_match("([{}])[]{}()"));
+    ::core::assert!(bracket_match("no brackets at all"));
+}
+
+#[test]
+fn rejects_unbalanced_brackets() {
+    ::core::assert!(!bracket_match("("));
+    ::core::assert!(!bracket_match(")"));
+    ::core::assert!(!bracket_match("(]"));
+    ::core::assert!(!bracket_match("([)]"));
+    ::core::assert!(!bracket_match("{}}{"));
+}
+
+#[test]
+fn stack_pushes_and_pops() {
+    let mut stack = Stack::new();
+    ::core::assert!(stack.is_empty());
+    stack.push(1);
+    stack.push(2);
+    ::core::assert_eq!(stack.len(), 2);
+    ::core::assert_eq!(stack.peek(), Some(&2));
+    ::core::assert_eq!(stack.pop(), Some(2));
+    ::core::assert_eq!(stack.pop(), Some(1));
+    ::core::assert_eq!(stack.pop(), None);
+}
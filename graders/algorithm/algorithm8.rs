@@ -0,0 +1,29 @@
+use super::*;
+
+#[test]
+fn pops_in_reverse_order() {
+    let mut stack = myStack::new();
+    for value in 1..=5 {
+        stack.push(value);
+    }
+    for value in (1..=5).rev() {
+        ::core::assert_eq!(stack.pop(), Ok(value));
+    }
+    ::core::assert!(stack.is_empty());
+    ::core::assert_eq!(stack.pop(), Err("Stack is empty"));
+}
+
+#[test]
+fn interleaves_pushes_and_pops() {
+    let mut stack = myStack::new();
+    stack.push("a");
+    stack.push("b");
+    ::core::assert_eq!(stack.pop(), Ok("b"));
+    stack.push("c");
+    stack.push("d");
+    ::core::assert_eq!(stack.pop(), Ok("d"));
+    ::core::assert_eq!(stack.pop(), Ok("c"));
+    ::core::assert!(!stack.is_empty());
+    ::core::assert_eq!(stack.pop(), Ok("a"));
+    ::core::assert!(stack.is_empty());
+}
@@ -0,0 +1,44 @@
+use super::*;
+
+#[test]
+fn min_heap_yields_values_in_ascending_order() {
+    let mut heap = MinHeap::new();
+    for value in [7, 3, 9, 1, 8, 2, 6, 5, 4] {
+        heap.add(value);
+    }
+    ::core::assert_eq!(heap.len(), 9);
+    let values: ::std::vec::Vec<i32> = heap.collect();
+    ::core::assert_eq!(values, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn max_heap_yields_values_in_descending_order() {
+    let mut heap = MaxHeap::new();
+    for value in [7, 3, 9, 1, 8, 2, 6, 5, 4] {
+        heap.add(value);
+    }
+    let values: ::std::vec::Vec<i32> = heap.collect();
+    ::core::assert_eq!(values, [9, 8, 7, 6, 5, 4, 3, 2, 1]);
+}
+
+#[test]
+fn keeps_duplicates() {
+    let mut heap = MinHeap::new();
+    for value in [2, 1, 2, 1] {
+        heap.add(value);
+    }
+    let values: ::std::vec::Vec<i32> = heap.collect();
+    ::core::assert_eq!(values, [1, 1, 2, 2]);
+}
+
+#[test]
+fn tracks_its_length() {
+    let mut heap = MaxHeap::new::<i32>();
+    ::core::assert!(heap.is_empty());
+    heap.add(1);
+    heap.add(2);
+    ::core::assert_eq!(heap.len(), 2);
+    heap.next();
+    ::core::assert_eq!(heap.len(), 1);
+    ::core::assert!(!heap.is_empty());
+}
@@ -0,0 +1,131 @@
+use crate::deps::dependencies;
+use crate::exercise::Exercise;
+use crate::state::fnv1a;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+// Where `verify` remembers the exercises that passed, next to info.toml
+pub const CACHE_FILE: &str = ".rustlings/cache.json";
+
+// The exercises that passed, along with the key they passed with
+#[derive(Deserialize, Serialize, Default, Debug)]
+struct VerifyCache {
+    #[serde(default)]
+    passed: BTreeMap<String, String>,
+}
+
+impl VerifyCache {
+    fn load_from(path: &Path) -> VerifyCache {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_to(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self).unwrap())
+    }
+}
+
+// The output of `rustc -vV`, which changes whenever the toolchain does
+fn toolchain() -> &'static str {
+    static TOOLCHAIN: OnceLock<String> = OnceLock::new();
+    TOOLCHAIN.get_or_init(|| {
+        Command::new("rustc")
+            .arg("-vV")
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            .unwrap_or_default()
+    })
+}
+
+// The key an exercise is cached with. It changes whenever the source of the
+// exercise, a file it depends on, its entry in info.toml, a file it's checked
+// against like its grader, or the toolchain it would be compiled with changes.
+fn cache_key(exercise: &Exercise) -> Option<String> {
+    let mut bytes = toolchain().as_bytes().to_vec();
+    bytes.push(0);
+    bytes.extend(serde_json::to_vec(exercise).ok()?);
+    if !exercise.path.exists() {
+        return None;
+    }
+    for file in dependencies(exercise)
+        .into_iter()
+        .chain(exercise.check_files())
+    {
+        bytes.push(0);
+        bytes.extend(fs::read(file).unwrap_or_default());
+    }
+    Some(format!("{:016x}", fnv1a(&bytes)))
+}
+
+// Whether the exercise passed before and nothing changed since
+pub fn is_cached(exercise: &Exercise) -> bool {
+    let cache = VerifyCache::load_from(Path::new(CACHE_FILE));
+    match cache.passed.get(&exercise.name) {
+        Some(key) => cache_key(exercise).as_ref() == Some(key),
+        None => false,
+    }
+}
+
+// Remember whether the exercise passed. Failing to save the cache
+// only means the exercise is compiled again next time, so errors are ignored.
+pub fn record(exercise: &Exercise, passed: bool) {
+    let path = Path::new(CACHE_FILE);
+    let mut cache = VerifyCache::load_from(path);
+    match cache_key(exercise) {
+        Some(key) if passed => cache.passed.insert(exercise.name.clone(), key),
+        _ => cache.passed.remove(&exercise.name),
+    };
+    let _ = cache.save_to(path);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::{ExpectedOutput, Mode, OutputMatch};
+    use std::{env, process};
+
+    #[test]
+    fn test_cache_key() {
+        let mut exercise = Exercise {
+            name: "finished_exercise".into(),
+            path: "tests/fixture/state/finished_exercise.rs".into(),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let key = cache_key(&exercise).unwrap();
+        assert_eq!(cache_key(&exercise).unwrap(), key);
+
+        exercise.mode = Mode::Clippy;
+        assert_ne!(cache_key(&exercise).unwrap(), key);
+        let key = cache_key(&exercise).unwrap();
+
+        exercise.timeout = Some(1);
+        assert_ne!(cache_key(&exercise).unwrap(), key);
+
+        // The contents of the golden file count, not only where it is
+        let golden = env::temp_dir().join(format!("rustlings_cache_key_{}.out", process::id()));
+        fs::write(&golden, "1").unwrap();
+        exercise.expected_output = Some(ExpectedOutput::Spec {
+            text: None,
+            file: Some(golden.clone()),
+            matching: OutputMatch::Trimmed,
+        });
+        let key = cache_key(&exercise).unwrap();
+        fs::write(&golden, "2").unwrap();
+        assert_ne!(cache_key(&exercise).unwrap(), key);
+        fs::remove_file(&golden).unwrap();
+
+        exercise.path = "tests/fixture/state/missing.rs".into();
+        assert_eq!(cache_key(&exercise), None);
+    }
+}
@@ -0,0 +1,413 @@
+use crate::diagnostics::Diagnostic;
+use crate::exercise::{CompiledExercise, Exercise, ExerciseList, ExerciseOutput, Mode, Stage};
+use crate::integrity;
+use crate::libtest::{self, TestResult};
+use crate::state::ProgressState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio::task;
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseCheckList {
+    pub exercises: Vec<ExerciseResult>,
+    pub user_name: Option<String>,
+    pub statistics: ExerciseStatistics,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseResult {
+    pub name: String,
+    pub category: String,
+    pub mode: Mode,
+    // The weight of the exercise in the score
+    pub points: u32,
+    pub result: bool,
+    // The stage the exercise failed at, if it failed
+    pub stage: Option<Stage>,
+    pub timed_out: bool,
+    pub time_ms: u64,
+    // The exit code of the last command that was run for the exercise
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    // The compiler's messages, if the exercise failed to compile
+    pub diagnostics: Vec<Diagnostic>,
+    // How many of its hints the user revealed
+    pub hints_used: usize,
+    // The result of every test, for exercises that are tested
+    pub tests: Vec<TestResult>,
+    // How the grader tests of the exercise did, if it has any and it compiled.
+    // `result` is only true if they passed as well as the tests of the exercise.
+    pub grader: Option<GraderResult>,
+    // Whether the tests of the exercise differ from the original ones in
+    // a way that can make them pass without checking anything
+    pub tests_modified: bool,
+    // How the tests differ from the original ones
+    pub modifications: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct GraderResult {
+    pub result: bool,
+    pub timed_out: bool,
+    pub tests: Vec<TestResult>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseStatistics {
+    pub total_exercations: usize,
+    pub total_succeeds: usize,
+    pub total_failures: usize,
+    // How long the run took in seconds, kept alongside the more precise
+    // `total_time_ms` for the consumers that read it
+    pub total_time: u32,
+    #[serde(default)]
+    pub total_time_ms: u64,
+    pub total_points: u32,
+    pub earned_points: u32,
+    // The earned points as a percentage of the total points
+    pub score: f64,
+    pub total_hints_used: usize,
+    pub categories: BTreeMap<String, CategoryStatistics>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct CategoryStatistics {
+    pub total_exercations: usize,
+    pub total_succeeds: usize,
+    pub total_points: u32,
+    pub earned_points: u32,
+}
+
+impl ExerciseStatistics {
+    // Count the result towards the totals of the run and of its category
+    fn record(&mut self, result: &ExerciseResult) {
+        let category = self.categories.entry(result.category.clone()).or_default();
+        category.total_exercations += 1;
+        category.total_points += result.points;
+        self.total_points += result.points;
+        self.total_hints_used += result.hints_used;
+        if result.result {
+            category.total_succeeds += 1;
+            category.earned_points += result.points;
+            self.total_succeeds += 1;
+            self.earned_points += result.points;
+        } else {
+            self.total_failures += 1;
+        }
+        if self.total_points > 0 {
+            self.score = f64::from(self.earned_points) * 100.0 / f64::from(self.total_points);
+        }
+    }
+}
+
+// How much of the output of an exercise is kept in its result
+const MAX_OUTPUT_LEN: usize = 4000;
+
+// Strip the colors from the output and cut out its middle if it's too long,
+// keeping the beginning and the end which are usually the interesting parts
+fn truncate_output(output: &str) -> String {
+    let output = console::strip_ansi_codes(output);
+    if output.len() <= MAX_OUTPUT_LEN {
+        return output.to_string();
+    }
+
+    let mut head = MAX_OUTPUT_LEN / 2;
+    while !output.is_char_boundary(head) {
+        head -= 1;
+    }
+    let mut tail = output.len() - MAX_OUTPUT_LEN / 2;
+    while !output.is_char_boundary(tail) {
+        tail += 1;
+    }
+    format!(
+        "{}\n... ({} bytes truncated) ...\n{}",
+        &output[..head],
+        tail - head,
+        &output[tail..]
+    )
+}
+
+// The name of the user from the git configuration, if there is one
+pub fn git_user_name() -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "user.name"])
+        .output()
+        .ok()?;
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() && !name.is_empty() {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+// The exercises as the info.toml of the original exercises in `pristine_dir`
+// lists them, with their graders taken from there as well. Users can change
+// their own info.toml and graders, e.g. to drop a grader or raise the points.
+pub fn original_exercises(pristine_dir: &Path) -> Option<Vec<Exercise>> {
+    let toml_str = fs::read_to_string(pristine_dir.join("info.toml")).ok()?;
+    let list = toml::from_str::<ExerciseList>(&toml_str).ok()?;
+    Some(
+        list.exercises
+            .into_iter()
+            .map(|exercise| Exercise {
+                grader: exercise.grader.map(|grader| pristine_dir.join(grader)),
+                ..exercise
+            })
+            .collect(),
+    )
+}
+
+// The number of exercises verified at once when `--jobs` isn't given
+pub fn default_jobs() -> usize {
+    thread::available_parallelism()
+        .map(|jobs| jobs.get())
+        .unwrap_or(1)
+}
+
+// The result of checking an exercise, and of its grader tests if they were run
+type CheckResult = (
+    Result<ExerciseOutput, ExerciseOutput>,
+    Option<Result<ExerciseOutput, ExerciseOutput>>,
+);
+
+// Compile and run a single exercise along with its grader tests,
+// capturing the output instead of printing it
+fn check(exercise: &Exercise) -> CheckResult {
+    match exercise.compile_with_grader().map(CompiledExercise::timed) {
+        Ok(compilation) => (compilation.run(), compilation.run_grader()),
+        Err(output) => (Err(output), None),
+    }
+}
+
+// Verify all exercises, running at most `jobs` compilations at the same time.
+// The compiler work happens on tokio's dedicated blocking threads so it never
+// starves the runtime, and the results are collected in the order of the
+// given exercises, no matter in which order they finish.
+// The tests of the exercises are checked against the original versions of
+// the exercises in `pristine_dir`, if there is one.
+pub async fn cicv_verify(
+    exercises: Vec<Exercise>,
+    jobs: usize,
+    pristine_dir: Option<PathBuf>,
+) -> ExerciseCheckList {
+    let start = Instant::now();
+    let total = exercises.len();
+    let progress = ProgressState::load();
+    let semaphore = Arc::new(Semaphore::new(jobs.max(1)));
+
+    let tasks: Vec<_> = exercises
+        .into_iter()
+        .map(|exercise| {
+            let semaphore = Arc::clone(&semaphore);
+            task::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.unwrap();
+                task::spawn_blocking(move || {
+                    let start = Instant::now();
+                    let result = check(&exercise);
+                    (exercise, result, start.elapsed())
+                })
+                .await
+                .unwrap()
+            })
+        })
+        .collect();
+
+    let mut check_list = ExerciseCheckList {
+        exercises: Vec::with_capacity(total),
+        user_name: None,
+        statistics: ExerciseStatistics {
+            total_exercations: total,
+            total_succeeds: 0,
+            total_failures: 0,
+            total_time: 0,
+            total_time_ms: 0,
+            total_points: 0,
+            earned_points: 0,
+            score: 0.0,
+            total_hints_used: 0,
+            categories: BTreeMap::new(),
+        },
+    };
+    for task in tasks {
+        let (exercise, (result, grader), elapsed) = task.await.unwrap();
+        let (passed, output) = match result {
+            // The tests of a test exercise can all be left out of its harness,
+            // which then passes without checking anything
+            Ok(output)
+                if matches!(exercise.mode, Mode::Test | Mode::Cargo)
+                    && output.tests().is_empty() =>
+            {
+                println!("{}没有运行任何测试", exercise.name);
+                (false, output)
+            }
+            Ok(output) => {
+                println!("{}执行成功", exercise.name);
+                (true, output)
+            }
+            Err(output) => {
+                if output.timed_out {
+                    println!("{}执行超时", exercise.name);
+                } else {
+                    println!("{}执行失败", exercise.name);
+                }
+                println!("{}", output.stdout);
+                println!("{}", output.stderr);
+                let tests = output.tests();
+                if !tests.is_empty() {
+                    println!("{}", libtest::summary(&tests));
+                }
+                (false, output)
+            }
+        };
+        let grader = grader.map(|grader| {
+            let (passed, output) = match grader {
+                // Like the tests of the exercise, a grader that ran no tests
+                // didn't check anything
+                Ok(output) if output.tests().is_empty() => {
+                    println!("{}的评分测试没有运行任何测试", exercise.name);
+                    (false, output)
+                }
+                Ok(output) => (true, output),
+                Err(output) => (false, output),
+            };
+            if !passed {
+                println!("{}的评分测试失败", exercise.name);
+                println!("{}", output.stdout);
+                println!("{}", libtest::summary(&output.tests()));
+            }
+            (passed, output)
+        });
+        let mut tamperings = Vec::new();
+        if let Some(pristine_dir) = &pristine_dir {
+            tamperings.extend(integrity::check(&exercise, pristine_dir));
+            if passed {
+                let ran = output.tests().len();
+                tamperings.extend(integrity::check_tests_run(&exercise, pristine_dir, ran));
+            }
+        }
+        let modifications: Vec<String> = tamperings
+            .iter()
+            .map(|tampering| tampering.to_string())
+            .collect();
+        if !modifications.is_empty() {
+            println!("{}的测试被修改过:", exercise.name);
+            for modification in &modifications {
+                println!("  {modification}");
+            }
+        }
+        let grader_passed = grader.as_ref().is_none_or(|(passed, _)| *passed);
+        let stage = if !passed {
+            Some(output.stage)
+        } else if !grader_passed {
+            Some(Stage::Grader)
+        } else {
+            None
+        };
+        let passed = passed && grader_passed;
+        let result = ExerciseResult {
+            category: exercise.category(),
+            points: exercise.points(),
+            hints_used: progress.hints_used(&exercise),
+            name: exercise.name,
+            mode: exercise.mode,
+            result: passed,
+            stage,
+            timed_out: output.timed_out,
+            time_ms: elapsed.as_millis() as u64,
+            exit_code: output.exit_code,
+            stdout: truncate_output(&output.stdout),
+            stderr: truncate_output(&output.stderr),
+            tests: output.tests(),
+            diagnostics: output
+                .diagnostics
+                .into_iter()
+                .filter(Diagnostic::is_relevant)
+                .collect(),
+            grader: grader.map(|(passed, output)| GraderResult {
+                result: passed,
+                timed_out: output.timed_out,
+                tests: output.tests(),
+                stdout: truncate_output(&output.stdout),
+                stderr: truncate_output(&output.stderr),
+            }),
+            tests_modified: !modifications.is_empty(),
+            modifications,
+        };
+        check_list.statistics.record(&result);
+        check_list.exercises.push(result);
+        println!("总的题目数: {}", total);
+        println!(
+            "当前做正确的题目数: {}",
+            check_list.statistics.total_succeeds
+        );
+        println!("当前修改试卷耗时: {} ms", elapsed.as_millis());
+    }
+    let total_time = start.elapsed();
+    check_list.statistics.total_time = total_time.as_secs() as u32;
+    check_list.statistics.total_time_ms = total_time.as_millis() as u64;
+    check_list
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_truncate_output() {
+        assert_eq!(truncate_output("\x1b[31mshort\x1b[0m"), "short");
+
+        let output = "é".repeat(MAX_OUTPUT_LEN);
+        let truncated = truncate_output(&output);
+        assert!(truncated.len() < output.len());
+        assert!(truncated.contains("bytes truncated"));
+    }
+
+    #[test]
+    fn test_results_in_order() {
+        let exercise = |name: &str, dir: &str, mode: Mode, timeout: Option<u64>| Exercise {
+            name: name.into(),
+            path: format!("tests/fixture/{dir}/{name}.rs").into(),
+            mode,
+            timeout,
+            ..Default::default()
+        };
+        // The first one takes the longest, so it finishes last when the
+        // exercises are verified at the same time
+        let exercises = vec![
+            exercise("timeout", "failure", Mode::Compile, Some(1)),
+            exercise("compSuccess", "success", Mode::Compile, None),
+            exercise("testFailure", "failure", Mode::Test, None),
+            exercise("testSuccess", "success", Mode::Test, None),
+        ];
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        for jobs in [1, exercises.len()] {
+            let check_list = runtime.block_on(cicv_verify(exercises.clone(), jobs, None));
+            let results: Vec<(&str, bool)> = check_list
+                .exercises
+                .iter()
+                .map(|result| (result.name.as_str(), result.result))
+                .collect();
+            assert_eq!(
+                results,
+                [
+                    ("timeout", false),
+                    ("compSuccess", true),
+                    ("testFailure", false),
+                    ("testSuccess", true),
+                ],
+                "with {jobs} jobs"
+            );
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use crate::exercise::{package_files, Exercise, Mode};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// Matches `mod name;` declarations, which load the module from another file
+const MOD_REGEX: &str = r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+(\w+)\s*;";
+// Matches `#[path = "file.rs"]` attributes, which point a module to a file
+const PATH_REGEX: &str = r#"#\[path\s*=\s*"([^"]+)"\]"#;
+// Matches the macros that pull another file into the source
+const INCLUDE_REGEX: &str = r#"include(?:_str|_bytes)?!\s*\(\s*"([^"]+)"\s*\)"#;
+
+fn regexes() -> &'static [Regex; 3] {
+    static REGEXES: OnceLock<[Regex; 3]> = OnceLock::new();
+    REGEXES.get_or_init(|| {
+        [
+            Regex::new(MOD_REGEX).unwrap(),
+            Regex::new(PATH_REGEX).unwrap(),
+            Regex::new(INCLUDE_REGEX).unwrap(),
+        ]
+    })
+}
+
+// Which exercises every file affects
+pub struct DependencyMap<'a> {
+    exercises: &'a [Exercise],
+    // The indices of the exercises, by the canonical path of the file
+    dependents: HashMap<PathBuf, Vec<usize>>,
+}
+
+impl<'a> DependencyMap<'a> {
+    pub fn new(exercises: &'a [Exercise]) -> Self {
+        let mut dependents: HashMap<PathBuf, Vec<usize>> = HashMap::new();
+        for (index, exercise) in exercises.iter().enumerate() {
+            for file in dependencies(exercise) {
+                dependents.entry(file).or_default().push(index);
+            }
+        }
+        DependencyMap {
+            exercises,
+            dependents,
+        }
+    }
+
+    // The indices of the exercises affected by changes to the files, in
+    // the order of the exercises. `files` are expected to be canonical paths.
+    pub fn affected(&self, files: &[PathBuf]) -> Vec<usize> {
+        let mut affected: Vec<usize> = files
+            .iter()
+            .filter_map(|file| self.dependents.get(file))
+            .flatten()
+            .copied()
+            .collect();
+        affected.sort_unstable();
+        affected.dedup();
+        affected
+    }
+
+    // The exercises affected by changes to the files, in their order
+    pub fn affected_exercises(&self, files: &[PathBuf]) -> Vec<&'a Exercise> {
+        self.affected(files)
+            .into_iter()
+            .map(|index| &self.exercises[index])
+            .collect()
+    }
+}
+
+// The canonical paths of the files the exercise is made of: the files the user
+// edits, see `Exercise::files`, and the files they pull in with `mod`, `#[path]`
+// and `include!`, following them into the modules, or the files of the packages
+// a Cargo exercise depends on by path
+pub fn dependencies(exercise: &Exercise) -> Vec<PathBuf> {
+    let mut files = exercise.files();
+    if let Mode::Cargo = exercise.mode {
+        for package in path_dependencies(&exercise.path) {
+            files.extend(package_files(&package));
+        }
+    }
+
+    // The exercise and its build script are the roots of their crates.
+    // Cargo exercises are made of all their files already.
+    let roots: Vec<PathBuf> = files.iter().filter_map(|f| f.canonicalize().ok()).collect();
+    let mut seen = HashSet::new();
+    let mut dependencies = Vec::new();
+    while let Some(file) = files.pop() {
+        let Ok(file) = file.canonicalize() else {
+            continue;
+        };
+        if !seen.insert(file.clone()) {
+            continue;
+        }
+        if file.extension().is_some_and(|ext| ext == "rs") {
+            files.extend(referenced_files(&file, roots.contains(&file)));
+        }
+        dependencies.push(file);
+    }
+    dependencies
+}
+
+// The directories of the packages the package in the directory depends on by
+// path, like `helper = { path = "../helper" }`, along with the ones they depend
+// on in turn
+fn path_dependencies(package: &Path) -> Vec<PathBuf> {
+    let mut packages = vec![package.to_path_buf()];
+    let mut seen = HashSet::new();
+    let mut dependencies = Vec::new();
+    while let Some(package) = packages.pop() {
+        let Ok(package) = package.canonicalize() else {
+            continue;
+        };
+        if !seen.insert(package.clone()) {
+            continue;
+        }
+        let Some(manifest) = fs::read_to_string(package.join("Cargo.toml"))
+            .ok()
+            .and_then(|manifest| manifest.parse::<toml::Value>().ok())
+        else {
+            continue;
+        };
+        for table in ["dependencies", "dev-dependencies", "build-dependencies"] {
+            let Some(table) = manifest.get(table).and_then(toml::Value::as_table) else {
+                continue;
+            };
+            packages.extend(
+                table
+                    .values()
+                    .filter_map(|dependency| dependency.get("path")?.as_str())
+                    .map(|path| package.join(path)),
+            );
+        }
+        dependencies.push(package);
+    }
+    // The first one is the package itself
+    dependencies.split_off(1.min(dependencies.len()))
+}
+
+// The files the source file refers to, whether they exist or not.
+// `is_root` tells whether the file is the root of its crate, whose
+// modules live next to it rather than in a directory named after it.
+fn referenced_files(file: &Path, is_root: bool) -> Vec<PathBuf> {
+    let Ok(source) = fs::read_to_string(file) else {
+        return Vec::new();
+    };
+    let dir = file.parent().unwrap_or_else(|| Path::new("."));
+    let module_dir = if is_root || file.file_name().is_some_and(|name| name == "mod.rs") {
+        dir.to_path_buf()
+    } else {
+        dir.join(file.file_stem().unwrap_or_default())
+    };
+
+    let [mod_regex, path_regex, include_regex] = regexes();
+    let mut files = Vec::new();
+    for captures in mod_regex.captures_iter(&source) {
+        let name = &captures[1];
+        files.push(module_dir.join(format!("{name}.rs")));
+        files.push(module_dir.join(name).join("mod.rs"));
+    }
+    for captures in path_regex
+        .captures_iter(&source)
+        .chain(include_regex.captures_iter(&source))
+    {
+        files.push(dir.join(&captures[1]));
+    }
+    files
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    fn exercise(name: &str, path: &str, mode: Mode) -> Exercise {
+        Exercise {
+            name: name.into(),
+            path: path.into(),
+            mode,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_dependencies() {
+        let exercise = exercise("modules", "tests/fixture/deps/modules.rs", Mode::Compile);
+        let dir = Path::new("tests/fixture/deps").canonicalize().unwrap();
+        let mut dependencies = dependencies(&exercise);
+        dependencies.sort();
+        assert_eq!(
+            dependencies,
+            [
+                dir.join("greeting.txt"),
+                dir.join("modules.rs"),
+                dir.join("shared").join("mod.rs"),
+                dir.join("shared").join("nested.rs"),
+                dir.join("util.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_path_dependencies() {
+        let exercise = exercise("shapes", "tests/fixture/cargo/shapes", Mode::Cargo);
+        let helper = Path::new("tests/fixture/cargo/helper/src/lib.rs")
+            .canonicalize()
+            .unwrap();
+        assert!(dependencies(&exercise).contains(&helper));
+    }
+
+    #[test]
+    fn test_affected_exercises() {
+        let exercises = [
+            exercise("modules", "tests/fixture/deps/modules.rs", Mode::Compile),
+            exercise("uses_util", "tests/fixture/deps/uses_util.rs", Mode::Test),
+            exercise(
+                "other",
+                "tests/fixture/state/pending_exercise.rs",
+                Mode::Compile,
+            ),
+        ];
+        let map = DependencyMap::new(&exercises);
+        let util = Path::new("tests/fixture/deps/util.rs")
+            .canonicalize()
+            .unwrap();
+        let affected: Vec<&str> = map
+            .affected_exercises(&[util])
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(affected, ["modules", "uses_util"]);
+    }
+}
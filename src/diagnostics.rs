@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::Path;
+
+// A compiler message, as reported by rustc's and cargo's JSON output.
+// Only the parts needed to summarize a failed compilation are kept.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    // The severity of the message, e.g. `error` or `warning`
+    pub level: String,
+    pub message: String,
+    // The error code or lint name, e.g. `E0381` or `clippy::approx_constant`
+    pub code: Option<String>,
+    // Where in the source code the message points to
+    pub span: Option<Span>,
+    // How the compiler suggests to fix the problem
+    pub suggestion: Option<String>,
+    // The message the way the compiler would have printed it
+    #[serde(skip)]
+    pub rendered: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Span {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    // The first line of source code the span covers
+    pub source: Option<String>,
+    // The columns of `source` that the span highlights
+    pub highlight: Option<(usize, usize)>,
+    pub label: Option<String>,
+}
+
+impl Diagnostic {
+    // Whether the message is about the exercise rather than a note like
+    // "aborting due to 1 previous error"
+    pub fn is_relevant(&self) -> bool {
+        (self.level == "error" || self.level == "warning") && self.span.is_some()
+    }
+
+    // Where to learn more about the error code or lint of the message
+    pub fn explanation(&self) -> Option<String> {
+        let code = self.code.as_deref()?;
+        if let Some(lint) = code.strip_prefix("clippy::") {
+            Some(format!(
+                "https://rust-lang.github.io/rust-clippy/master/index.html#{lint}"
+            ))
+        } else if code.starts_with('E') {
+            Some(format!("`rustc --explain {code}`"))
+        } else {
+            None
+        }
+    }
+}
+
+// The subset of rustc's JSON diagnostic format we read
+#[derive(Deserialize)]
+struct RawDiagnostic {
+    level: String,
+    message: String,
+    code: Option<RawCode>,
+    spans: Vec<RawSpan>,
+    children: Vec<RawDiagnostic>,
+    rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RawSpan {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    is_primary: bool,
+    text: Vec<RawText>,
+    label: Option<String>,
+    suggested_replacement: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawText {
+    text: String,
+    highlight_start: usize,
+    highlight_end: usize,
+}
+
+impl From<RawDiagnostic> for Diagnostic {
+    fn from(raw: RawDiagnostic) -> Self {
+        let span = raw
+            .spans
+            .into_iter()
+            .find(|span| span.is_primary)
+            .map(|span| {
+                let text = span.text.into_iter().next();
+                Span {
+                    file: relative_to_current_dir(&span.file_name),
+                    line: span.line_start,
+                    column: span.column_start,
+                    highlight: text
+                        .as_ref()
+                        .map(|text| (text.highlight_start, text.highlight_end)),
+                    source: text.map(|text| text.text),
+                    label: span.label,
+                }
+            });
+        let suggestion = raw
+            .children
+            .iter()
+            .find(|child| child.level == "help")
+            .map(|help| {
+                let replacement = help
+                    .spans
+                    .iter()
+                    .find_map(|span| span.suggested_replacement.as_deref())
+                    .filter(|replacement| !replacement.trim().is_empty());
+                match replacement {
+                    Some(replacement) => format!("{}: `{}`", help.message, replacement.trim()),
+                    None => help.message.clone(),
+                }
+            });
+
+        Diagnostic {
+            level: raw.level,
+            message: raw.message,
+            code: raw.code.map(|code| code.code),
+            span,
+            suggestion,
+            rendered: raw.rendered,
+        }
+    }
+}
+
+// Paths in diagnostics are shown relative to the rustlings directory when possible
+fn relative_to_current_dir(file: &str) -> String {
+    env::current_dir()
+        .ok()
+        .and_then(|dir| {
+            Path::new(file)
+                .strip_prefix(dir)
+                .ok()
+                .map(|path| path.display().to_string())
+        })
+        .unwrap_or_else(|| file.to_string())
+}
+
+// Parse the output of `rustc --error-format=json`.
+// Returns the diagnostics along with the text the compiler would have printed.
+pub fn parse_rustc(stderr: &str) -> (Vec<Diagnostic>, String) {
+    let mut diagnostics = Vec::new();
+    let mut text = String::new();
+    for line in stderr.lines() {
+        match serde_json::from_str::<RawDiagnostic>(line) {
+            Ok(raw) => {
+                let diagnostic = Diagnostic::from(raw);
+                if let Some(rendered) = &diagnostic.rendered {
+                    text += rendered;
+                }
+                diagnostics.push(diagnostic);
+            }
+            Err(_) => {
+                text += line;
+                text.push('\n');
+            }
+        }
+    }
+    (diagnostics, text)
+}
+
+// The subset of cargo's `--message-format=json` messages we read
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<RawDiagnostic>,
+}
+
+// Parse the compiler messages out of the output of `cargo --message-format=json`.
+// Returns the diagnostics along with the text the compiler would have printed.
+pub fn parse_cargo(stdout: &str) -> (Vec<Diagnostic>, String) {
+    let mut diagnostics = Vec::new();
+    let mut text = String::new();
+    for message in stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CargoMessage>(line).ok())
+        .filter(|message| message.reason == "compiler-message")
+        .filter_map(|message| message.message)
+    {
+        let diagnostic = Diagnostic::from(message);
+        if let Some(rendered) = &diagnostic.rendered {
+            text += rendered;
+        }
+        diagnostics.push(diagnostic);
+    }
+    (diagnostics, text)
+}
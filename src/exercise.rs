@@ -1,35 +1,48 @@
+use crate::diagnostics::{self, Diagnostic};
+use crate::libtest::{self, Outcome, TestResult};
+use glob::{glob_with, MatchOptions};
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::env;
 use std::fmt::{self, Display, Formatter};
-use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::fs::{self, remove_dir_all, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{self, Command, ExitStatus, Output, Stdio};
+use std::slice;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
 const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
+const RUSTC_JSON_ARGS: &[&str] = &["--error-format=json", "--json=diagnostic-rendered-ansi"];
+const CARGO_JSON_ARGS: &[&str] = &["--message-format=json-diagnostic-rendered-ansi"];
 const RUSTC_EDITION_ARGS: &[&str] = &["--edition", "2021"];
 const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
-const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
-const BUILD_SCRIPT_CARGO_TOML_PATH: &str = "./exercises/tests/Cargo.toml";
-
-// Get a temporary file name that is hopefully unique
-#[inline]
-fn temp_file() -> String {
-    let thread_id: String = format!("{:?}", std::thread::current().id())
-        .chars()
-        .filter(|c| c.is_alphanumeric())
-        .collect();
+// How long a compiled exercise may run unless its info.toml entry says otherwise
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+// The module the grader tests are added to the exercise as, whose name
+// prefixes the names of the grader tests at the root of the crate
+const GRADER_MODULE: &str = "grader";
+// Matches what must stay at the start of a source file, before the grader
+// module is declared: comments, and inner attributes like `#![allow(..)]`
+const FILE_HEADER_REGEX: &str = r"\A(?:\s+|//[^\n]*(?:\n|\z)|(?s:/\*.*?\*/)|#!\[[^\]]*\])*";
+
+// Get a scratch directory path that is unique to this process and compilation
+fn scratch_dir() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let id = COUNTER.fetch_add(1, Ordering::SeqCst);
 
-    format!("./temp_{}_{thread_id}", process::id())
+    env::temp_dir().join(format!("rustlings_{}_{id}", process::id()))
 }
 
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
+    #[default]
     Compile,
     // Indicates that the exercise should be compiled as a test harness
     Test,
@@ -37,6 +50,9 @@ pub enum Mode {
     Clippy,
     // Indicates that the exercise should be run using cargo with build script
     BuildScript,
+    // Indicates that the exercise is a directory with its own Cargo.toml,
+    // whose tests should be run using cargo
+    Cargo,
 }
 
 #[derive(Deserialize)]
@@ -46,7 +62,7 @@ pub struct ExerciseList {
 
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
@@ -55,7 +71,169 @@ pub struct Exercise {
     // The mode of the exercise (Test, Compile, or Clippy)
     pub mode: Mode,
     // The hint text associated with the exercise
-    pub hint: String,
+    pub hint: Hint,
+    // The number of seconds the compiled exercise may run before it's stopped
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    // How many points solving the exercise is worth when grading
+    #[serde(default)]
+    pub points: Option<u32>,
+    // How hard the exercise is, from 1 for the easiest ones
+    #[serde(default)]
+    pub difficulty: Option<u8>,
+    // The topics the exercise is about, like `ownership`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // The names of the exercises that should be done before this one
+    #[serde(default)]
+    pub requires: Vec<String>,
+    // About how long the exercise takes to solve
+    #[serde(default)]
+    pub estimated_minutes: Option<u32>,
+    // What the compiled exercise should print when it runs
+    #[serde(default)]
+    pub expected_output: Option<ExpectedOutput>,
+    // Inputs to run the compiled exercise with, one run for each,
+    // along with what it should print for them
+    #[serde(default)]
+    pub cases: Vec<Case>,
+    // A file of tests that `cicvverify` adds to a test exercise, which
+    // still check it if the user tampered with the tests in the exercise
+    #[serde(default)]
+    pub grader: Option<PathBuf>,
+}
+
+// The hint of an exercise. It is either a single text, or a list of hints
+// from vague to specific that are revealed one after the other.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Hint {
+    Single(String),
+    Levels(Vec<String>),
+}
+
+impl Hint {
+    pub fn levels(&self) -> &[String] {
+        match self {
+            Hint::Single(hint) => slice::from_ref(hint),
+            Hint::Levels(hints) => hints,
+        }
+    }
+}
+
+impl Default for Hint {
+    fn default() -> Self {
+        Hint::Single(String::new())
+    }
+}
+
+// All levels of the hint at once
+impl Display for Hint {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.levels().join("\n\n"))
+    }
+}
+
+// The output a compiled exercise should print. It is either the text itself,
+// which is compared with the output trimmed, or a table with the `text` or the
+// `file` holding it, like `{ file = "exercises/intro/intro1.out" }`, and how
+// it's compared, like `match = "regex"`.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ExpectedOutput {
+    Inline(String),
+    Spec {
+        text: Option<String>,
+        // A golden file, relative to the directory rustlings runs in
+        file: Option<PathBuf>,
+        #[serde(default, rename = "match")]
+        matching: OutputMatch,
+    },
+}
+
+impl ExpectedOutput {
+    // The expected output, read from the golden file if there is one
+    pub fn text(&self) -> io::Result<String> {
+        match self {
+            ExpectedOutput::Inline(text)
+            | ExpectedOutput::Spec {
+                text: Some(text), ..
+            } => Ok(text.clone()),
+            ExpectedOutput::Spec {
+                file: Some(file), ..
+            } => fs::read_to_string(file),
+            ExpectedOutput::Spec { .. } => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the expected output needs either a `text` or a `file`",
+            )),
+        }
+    }
+
+    // The golden file the expected output is read from, if there is one
+    pub fn file(&self) -> Option<&Path> {
+        match self {
+            ExpectedOutput::Spec {
+                text: None,
+                file: Some(file),
+                ..
+            } => Some(file),
+            _ => None,
+        }
+    }
+
+    pub fn matching(&self) -> OutputMatch {
+        match self {
+            ExpectedOutput::Inline(_) => OutputMatch::default(),
+            ExpectedOutput::Spec { matching, .. } => *matching,
+        }
+    }
+}
+
+// An input for a compiled exercise that reads its standard input,
+// and the output it should print for it
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Case {
+    // What is written to the standard input of the binary
+    pub input: String,
+    pub expected_output: ExpectedOutput,
+}
+
+// How the output of an exercise is compared with the expected one
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMatch {
+    // The output must be exactly the same
+    Exact,
+    // Whitespace at the end of the lines and blank lines around
+    // the output don't matter
+    #[default]
+    Trimmed,
+    // The trimmed output must match the regular expression as a whole
+    Regex,
+}
+
+impl OutputMatch {
+    pub fn matches(self, expected: &str, output: &str) -> bool {
+        match self {
+            OutputMatch::Exact => output == expected,
+            OutputMatch::Trimmed => trim_output(output) == trim_output(expected),
+            OutputMatch::Regex => Regex::new(&format!("^(?:{expected})$"))
+                .is_ok_and(|regex| regex.is_match(&trim_output(output))),
+        }
+    }
+
+    // The text as it is compared, to show how it differs from the other one
+    pub fn normalize(self, text: &str) -> String {
+        match self {
+            OutputMatch::Trimmed => trim_output(text),
+            OutputMatch::Exact | OutputMatch::Regex => text.to_string(),
+        }
+    }
+}
+
+fn trim_output(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().map(str::trim_end).collect();
+    lines.join("\n").trim().to_string()
 }
 
 // An enum to track of the state of an Exercise.
@@ -79,16 +257,234 @@ pub struct ContextLine {
     pub important: bool,
 }
 
+// The stage of checking an exercise that produced an output
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    // Compiling the exercise with rustc or cargo
+    Compile,
+    // Linting the exercise with clippy
+    Clippy,
+    // Running the compiled binary
+    Run,
+    // Running the compiled test harness
+    Test,
+    // Running the grader tests added to the test harness
+    Grader,
+    // Comparing what the compiled binary printed with the expected output
+    Output,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let stage = match self {
+            Stage::Compile => "compile",
+            Stage::Clippy => "clippy",
+            Stage::Run => "run",
+            Stage::Test => "test",
+            Stage::Grader => "grader",
+            Stage::Output => "output",
+        };
+        write!(f, "{stage}")
+    }
+}
+
 // The result of compiling an exercise
 pub struct CompiledExercise<'a> {
     exercise: &'a Exercise,
+    // The binaries or test harnesses to run, one after the other
+    harnesses: Vec<Harness>,
+    // The names of the grader tests compiled into the test harness, if any,
+    // which are then left out when running the tests of the exercise
+    grader_tests: Option<Vec<String>>,
+    // Whether the test harnesses report how long every test took
+    report_time: bool,
     _handle: FileHandle,
 }
 
+// A binary or test harness of a compiled exercise
+#[derive(PartialEq, Debug)]
+struct Harness {
+    binary: PathBuf,
+    // The directory to run it in, if not the current one
+    dir: Option<PathBuf>,
+    // Environment variables a build script asked to set when running
+    env: Vec<(String, String)>,
+}
+
+impl Harness {
+    fn new(binary: PathBuf) -> Self {
+        Harness {
+            binary,
+            dir: None,
+            env: Vec::new(),
+        }
+    }
+}
+
 impl<'a> CompiledExercise<'a> {
-    // Run the compiled exercise
+    // Run the compiled exercise, and check what it printed if it's expected to print something
     pub fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        self.exercise.run()
+        if !self.exercise.cases.is_empty() {
+            return self.run_cases();
+        }
+        let output = self.run_harnesses(None)?;
+        match &self.exercise.expected_output {
+            Some(expected) => check_output(expected, output),
+            None => Ok(output),
+        }
+    }
+
+    // Run the compiled exercise once for every case, feeding it the input of the case.
+    // The output is the one of the first case that failed, or of the last case if they
+    // all passed, along with the outcome of every case.
+    fn run_cases(&self) -> Result<ExerciseOutput, ExerciseOutput> {
+        let cases: Vec<CaseOutcome> = self
+            .exercise
+            .cases
+            .iter()
+            .map(|case| {
+                let result = self
+                    .run_harnesses(Some(&case.input))
+                    .and_then(|output| check_output(&case.expected_output, output));
+                CaseOutcome {
+                    passed: result.is_ok(),
+                    output: result.unwrap_or_else(|output| output),
+                }
+            })
+            .collect();
+        let failed = cases.iter().position(|case| !case.passed);
+        let shown = &cases[failed.unwrap_or(cases.len() - 1)].output;
+        let output = ExerciseOutput {
+            stdout: shown.stdout.clone(),
+            stderr: shown.stderr.clone(),
+            stage: shown.stage,
+            exit_code: shown.exit_code,
+            timed_out: shown.timed_out,
+            diagnostics: Vec::new(),
+            cases,
+        };
+        match failed {
+            Some(_) => Err(output),
+            None => Ok(output),
+        }
+    }
+
+    // Have the test harnesses report how long every test took
+    pub fn timed(self) -> Self {
+        CompiledExercise {
+            report_time: true,
+            ..self
+        }
+    }
+
+    // Run the grader tests compiled into the test harness, if there are any.
+    // They only pass if every one of them shows up as passed in the output,
+    // since the exercise can end the process successfully before they run.
+    pub fn run_grader(&self) -> Option<Result<ExerciseOutput, ExerciseOutput>> {
+        let tests = self.grader_tests.as_ref()?;
+        if tests.is_empty() {
+            return Some(Ok(ExerciseOutput::empty(Stage::Grader)));
+        }
+        // Filter by the exact names, so that no test of the exercise
+        // sneaks in by having `grader::` somewhere in its name
+        let mut cmd = Command::new(&self.harnesses[0].binary);
+        cmd.arg("--exact").args(tests);
+        self.add_report_time(&mut cmd);
+        let with_stage = |mut output: ExerciseOutput| {
+            output.stage = Stage::Grader;
+            output
+        };
+        let result = self
+            .exercise
+            .run(cmd, None)
+            .map(with_stage)
+            .map_err(with_stage);
+        Some(result.and_then(|mut output| {
+            let results = output.tests();
+            let missing: Vec<&str> = tests
+                .iter()
+                .filter(|test| {
+                    !results
+                        .iter()
+                        .any(|result| &result.name == *test && result.outcome == Outcome::Passed)
+                })
+                .map(String::as_str)
+                .collect();
+            if missing.is_empty() {
+                return Ok(output);
+            }
+            output.stderr += &format!("These grader tests didn't pass: {}\n", missing.join(", "));
+            Err(output)
+        }))
+    }
+
+    // `--report-time` is an unstable libtest option, which the test harnesses
+    // of a stable toolchain only accept with `RUSTC_BOOTSTRAP` set
+    fn add_report_time(&self, cmd: &mut Command) {
+        if self.report_time
+            && matches!(
+                self.exercise.mode,
+                Mode::Test | Mode::BuildScript | Mode::Cargo
+            )
+        {
+            cmd.env("RUSTC_BOOTSTRAP", "1")
+                .args(["-Z", "unstable-options", "--report-time"]);
+        }
+    }
+
+    // Run the harnesses of the compiled exercise with the input, if any,
+    // stopping at the first one that fails
+    fn run_harnesses(&self, input: Option<&str>) -> Result<ExerciseOutput, ExerciseOutput> {
+        let mut output: Option<ExerciseOutput> = None;
+        for harness in &self.harnesses {
+            let mut cmd = Command::new(&harness.binary);
+            cmd.envs(harness.env.iter().map(|(key, value)| (key, value)));
+            if let Some(dir) = &harness.dir {
+                cmd.current_dir(dir);
+            }
+            if let Some(tests) = &self.grader_tests {
+                cmd.arg("--exact");
+                for test in tests {
+                    cmd.args(["--skip", test]);
+                }
+            }
+            self.add_report_time(&mut cmd);
+            let result = self.exercise.run(cmd, input);
+            let passed = result.is_ok();
+            let next = result.unwrap_or_else(|output| output);
+            let combined = match output.take() {
+                Some(previous) => previous.followed_by(next),
+                None => next,
+            };
+            if !passed {
+                return Err(combined);
+            }
+            output = Some(combined);
+        }
+        Ok(output.unwrap_or_else(|| ExerciseOutput::empty(Stage::Test)))
+    }
+}
+
+// Fail the output at the output stage unless it is the expected one
+fn check_output(
+    expected: &ExpectedOutput,
+    mut output: ExerciseOutput,
+) -> Result<ExerciseOutput, ExerciseOutput> {
+    let matches = match expected.text() {
+        Ok(text) => expected.matching().matches(&text, &output.stdout),
+        Err(e) => {
+            output
+                .stderr
+                .push_str(&format!("Couldn't read the expected output: {e}\n"));
+            false
+        }
+    };
+    if matches {
+        Ok(output)
+    } else {
+        output.stage = Stage::Output;
+        Err(output)
     }
 }
 
@@ -99,92 +495,283 @@ pub struct ExerciseOutput {
     pub stdout: String,
     // The textual contents of the standard error of the binary
     pub stderr: String,
+    // The stage that produced this output
+    pub stage: Stage,
+    // The exit code of the binary, if it exited normally
+    pub exit_code: Option<i32>,
+    // Whether the binary was stopped because it exceeded its timeout
+    pub timed_out: bool,
+    // The messages of the compiler, if this is the output of a compilation
+    pub diagnostics: Vec<Diagnostic>,
+    // How the binary did on every case of the exercise, if it has any
+    pub cases: Vec<CaseOutcome>,
+}
+
+// How a compiled exercise did on one of its cases
+#[derive(Debug)]
+pub struct CaseOutcome {
+    // Whether it ran successfully and printed the expected output
+    pub passed: bool,
+    // What it printed for the input of the case
+    pub output: ExerciseOutput,
+}
+
+impl ExerciseOutput {
+    // The output of a stage that had nothing to say
+    pub fn empty(stage: Stage) -> Self {
+        ExerciseOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+            stage,
+            exit_code: Some(0),
+            timed_out: false,
+            diagnostics: Vec::new(),
+            cases: Vec::new(),
+        }
+    }
+
+    // The result of every test, if this is the output of a test harness
+    pub fn tests(&self) -> Vec<TestResult> {
+        match self.stage {
+            Stage::Test | Stage::Grader => libtest::parse(&self.stdout),
+            _ => Vec::new(),
+        }
+    }
+
+    // The output of this run followed by the output of the next one,
+    // which tells how the whole thing ended
+    fn followed_by(mut self, next: ExerciseOutput) -> Self {
+        self.stdout += &next.stdout;
+        self.stderr += &next.stderr;
+        self.diagnostics.extend(next.diagnostics);
+        ExerciseOutput {
+            stage: next.stage,
+            exit_code: next.exit_code,
+            timed_out: next.timed_out,
+            ..self
+        }
+    }
+
+    // The output of rustc invoked with `RUSTC_JSON_ARGS`
+    fn from_rustc(output: Output, stage: Stage) -> Self {
+        let (diagnostics, stderr) =
+            diagnostics::parse_rustc(&String::from_utf8_lossy(&output.stderr));
+        ExerciseOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr,
+            stage,
+            exit_code: output.status.code(),
+            timed_out: false,
+            diagnostics,
+            cases: Vec::new(),
+        }
+    }
+
+    // The output of cargo invoked with `CARGO_JSON_ARGS`
+    fn from_cargo(output: Output, stage: Stage) -> Self {
+        let (diagnostics, rendered) =
+            diagnostics::parse_cargo(&String::from_utf8_lossy(&output.stdout));
+        ExerciseOutput {
+            stdout: String::new(),
+            stderr: rendered + &String::from_utf8_lossy(&output.stderr),
+            stage,
+            exit_code: output.status.code(),
+            timed_out: false,
+            diagnostics,
+            cases: Vec::new(),
+        }
+    }
 }
 
-struct FileHandle;
+// A scratch directory holding everything a single compilation produces:
+// the binary, the generated Cargo.toml and Cargo's target directory.
+// It is removed once the handle is dropped, so that several exercises
+// can be compiled at the same time without stepping on each other.
+struct FileHandle {
+    dir: PathBuf,
+}
+
+impl FileHandle {
+    fn new() -> FileHandle {
+        let dir = scratch_dir();
+        fs::create_dir_all(&dir).expect("Failed to create a scratch directory.");
+        FileHandle { dir }
+    }
+
+    // The path of the binary produced by rustc
+    fn binary(&self) -> PathBuf {
+        self.dir
+            .join(format!("exercise{}", env::consts::EXE_SUFFIX))
+    }
+
+    // The path of the Cargo.toml generated for cargo based modes
+    fn manifest(&self) -> PathBuf {
+        self.dir.join("Cargo.toml")
+    }
+
+    fn target_dir(&self) -> PathBuf {
+        self.dir.join("target")
+    }
+
+    // Write a Cargo.toml building the given exercise as a binary,
+    // using `build_script` as the package's build script if provided
+    fn write_manifest(&self, exercise: &Exercise, build_script: Option<&Path>) {
+        let mut cargo_toml = format!(
+            r#"[package]
+name = "{}"
+version = "0.0.1"
+edition = "2021"
+"#,
+            exercise.name
+        );
+        if let Some(build_script) = build_script {
+            cargo_toml += &format!("build = {:?}\n", absolute(build_script));
+        }
+        cargo_toml += &format!(
+            r#"[workspace]
+[[bin]]
+name = "{}"
+path = {:?}
+"#,
+            exercise.name,
+            absolute(&exercise.path)
+        );
+        let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
+            "Failed to write Cargo.toml file."
+        } else {
+            "Failed to write 📎 Cargo.toml 📎 file."
+        };
+        fs::write(self.manifest(), cargo_toml).expect(cargo_toml_error_msg);
+    }
+
+    // A cargo command operating on the generated Cargo.toml
+    fn cargo(&self, subcommand: &str) -> Command {
+        let mut cmd = Command::new("cargo");
+        cmd.arg(subcommand)
+            .arg("--manifest-path")
+            .arg(self.manifest())
+            .arg("--target-dir")
+            .arg(self.target_dir());
+        cmd
+    }
+}
 
 impl Drop for FileHandle {
     fn drop(&mut self) {
-        clean();
+        let _ignored = remove_dir_all(&self.dir);
     }
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
+    pub fn compile(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        let handle = FileHandle::new();
         let cmd = match self.mode {
             Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+                .arg(&self.path)
+                .arg("-o")
+                .arg(handle.binary())
+                .args(RUSTC_JSON_ARGS)
                 .args(RUSTC_EDITION_ARGS)
                 .output(),
             Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+                .arg("--test")
+                .arg(&self.path)
+                .arg("-o")
+                .arg(handle.binary())
+                .args(RUSTC_JSON_ARGS)
                 .args(RUSTC_EDITION_ARGS)
                 .output(),
             Mode::Clippy => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
-                };
-                fs::write(CLIPPY_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
+                handle.write_manifest(self, None);
                 // To support the ability to run the clippy exercises, build
-                // an executable, in addition to running clippy. With a
-                // compilation failure, this would silently fail. But we expect
-                // clippy to reflect the same failure while compiling later.
-                Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                    .args(RUSTC_COLOR_ARGS)
+                // an executable, in addition to running clippy. A compilation
+                // failure is reported as such, before clippy gets to lint.
+                let rustc = Command::new("rustc")
+                    .arg(&self.path)
+                    .arg("-o")
+                    .arg(handle.binary())
+                    .args(RUSTC_JSON_ARGS)
                     .args(RUSTC_EDITION_ARGS)
                     .output()
                     .expect("Failed to compile!");
-                // Due to an issue with Clippy, a cargo clean is required to catch all lints.
+                if !rustc.status.success() {
+                    return Err(ExerciseOutput::from_rustc(rustc, Stage::Compile));
+                }
+                // Every compilation gets a fresh target directory, so Clippy
+                // never skips linting because of a previous build.
                 // See https://github.com/rust-lang/rust-clippy/issues/2604
-                // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
-                // https://github.com/rust-lang/rust-clippy/issues/3837
-                Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                handle
+                    .cargo("clippy")
                     .args(RUSTC_COLOR_ARGS)
+                    .args(CARGO_JSON_ARGS)
+                    .args(["--", "-D", "warnings", "-D", "clippy::float_cmp"])
                     .output()
-                    .expect("Failed to run 'cargo clean'");
-                Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+            }
+            Mode::BuildScript => {
+                handle.write_manifest(self, Some(&self.build_script()));
+                // Cargo reports the path of the test harness it built in its
+                // JSON messages, which lets us run the harness ourselves
+                // instead of leaving it to a `cargo test` we can't time out.
+                let cmd = handle
+                    .cargo("test")
+                    .arg("--no-run")
                     .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
+                    .args(CARGO_JSON_ARGS)
                     .output()
-            },
-            Mode::BuildScript => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
+                    .expect("Failed to run 'compile' command.");
+                let messages = String::from_utf8_lossy(&cmd.stdout).to_string();
+                // The generated package lives in the scratch directory,
+                // but the harness keeps running from the current one
+                let harnesses: Vec<Harness> = test_harnesses(&messages)
+                    .into_iter()
+                    .map(|harness| Harness {
+                        dir: None,
+                        ..harness
+                    })
+                    .collect();
+                return if cmd.status.success() && !harnesses.is_empty() {
+                    Ok(CompiledExercise {
+                        exercise: self,
+                        harnesses,
+                        grader_tests: None,
+                        report_time: false,
+                        _handle: handle,
+                    })
                 } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
+                    Err(ExerciseOutput::from_cargo(cmd, Stage::Compile))
                 };
-                fs::write(BUILD_SCRIPT_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
-
-                Command::new("cargo")
-                    .args(&["test", "--manifest-path", BUILD_SCRIPT_CARGO_TOML_PATH])
+            }
+            Mode::Cargo => {
+                // The package is built where it is, so that its own
+                // `.cargo/config.toml` applies, which is where vendored
+                // dependencies are set up. Exercises must build offline.
+                let cmd = Command::new("cargo")
+                    .current_dir(&self.path)
+                    .args([
+                        "test",
+                        "--no-run",
+                        "--offline",
+                        "--workspace",
+                        "--all-targets",
+                    ])
+                    .arg("--target-dir")
+                    .arg(handle.target_dir())
+                    .args(RUSTC_COLOR_ARGS)
+                    .args(CARGO_JSON_ARGS)
                     .output()
+                    .expect("Failed to run 'compile' command.");
+                let messages = String::from_utf8_lossy(&cmd.stdout).to_string();
+                return if cmd.status.success() {
+                    Ok(CompiledExercise {
+                        exercise: self,
+                        harnesses: test_harnesses(&messages),
+                        grader_tests: None,
+                        report_time: false,
+                        _handle: handle,
+                    })
+                } else {
+                    Err(ExerciseOutput::from_cargo(cmd, Stage::Compile))
+                };
             }
         }
         .expect("Failed to run 'compile' command.");
@@ -192,91 +779,186 @@ path = "{}.rs""#,
         if cmd.status.success() {
             Ok(CompiledExercise {
                 exercise: self,
-                _handle: FileHandle,
+                harnesses: vec![Harness::new(handle.binary())],
+                grader_tests: None,
+                report_time: false,
+                _handle: handle,
             })
+        } else if let Mode::Clippy = self.mode {
+            Err(ExerciseOutput::from_cargo(cmd, Stage::Clippy))
         } else {
-            clean();
-            Err(ExerciseOutput {
-                stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
-            })
+            Err(ExerciseOutput::from_rustc(cmd, Stage::Compile))
         }
     }
 
-    fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        let arg = match self.mode {
-            Mode::Test => "--show-output",
-            Mode::BuildScript => return Ok(ExerciseOutput {
-                stdout: "".to_string(),
-                stderr: "".to_string(),
-            }),
-            _ => "",
+    // Compile a test exercise along with its grader tests, if it has any.
+    // They're added to a copy of the exercise in the scratch directory
+    // as a `grader` module, so they can `use super::*` like the tests of the
+    // exercise, and the user can't change them by changing the exercise.
+    // The module is declared before the code of the exercise, so that the
+    // macros it defines, like an `assert_eq!` that always passes, aren't in
+    // scope in the grader tests.
+    pub fn compile_with_grader(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        let (Some(grader), Mode::Test) = (&self.grader, self.mode) else {
+            return self.compile();
         };
-        println!("pa={}", temp_file());
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
+        let handle = FileHandle::new();
+        let copy = handle.dir.join(self.path.file_name().unwrap_or_default());
+        let written = grader.canonicalize().and_then(|grader| {
+            let source = fs::read_to_string(&self.path)?;
+            let header = Regex::new(FILE_HEADER_REGEX).unwrap();
+            let (header, code) = source.split_at(header.find(&source).map_or(0, |m| m.end()));
+            // On the same line as the code, so that the lines of the
+            // diagnostics still match the ones of the exercise
+            fs::write(
+                &copy,
+                format!("{header}#[cfg(test)] #[path = {grader:?}] mod {GRADER_MODULE}; {code}"),
+            )
+        });
+        if let Err(e) = written {
+            let mut output = ExerciseOutput::empty(Stage::Compile);
+            output.exit_code = None;
+            output.stderr = format!("Couldn't add the grader tests {}: {e}", grader.display());
+            return Err(output);
+        }
+
+        let cmd = Command::new("rustc")
+            .arg("--test")
+            .arg(&copy)
+            .arg("-o")
+            .arg(handle.binary())
+            .args(RUSTC_JSON_ARGS)
+            .args(RUSTC_EDITION_ARGS)
             .output()
-            .expect("Failed to run 'run' command");
+            .expect("Failed to run 'compile' command.");
+        if !cmd.status.success() {
+            return Err(ExerciseOutput::from_rustc(cmd, Stage::Compile));
+        }
+        match grader_tests(&handle.binary()) {
+            Ok(tests) => Ok(CompiledExercise {
+                exercise: self,
+                harnesses: vec![Harness::new(handle.binary())],
+                grader_tests: Some(tests),
+                report_time: false,
+                _handle: handle,
+            }),
+            Err(e) => {
+                let mut output = ExerciseOutput::empty(Stage::Grader);
+                output.exit_code = None;
+                output.stderr = format!("Couldn't list the grader tests: {e}");
+                Err(output)
+            }
+        }
+    }
 
-        let output = ExerciseOutput {
-            stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+    fn run(&self, mut cmd: Command, input: Option<&str>) -> Result<ExerciseOutput, ExerciseOutput> {
+        let stage = match self.mode {
+            Mode::Test | Mode::BuildScript | Mode::Cargo => {
+                cmd.arg("--show-output");
+                Stage::Test
+            }
+            Mode::Compile | Mode::Clippy => Stage::Run,
         };
+        let (output, status) = output_with_timeout(&mut cmd, self.timeout(), stage, input)
+            .expect("Failed to run 'run' command");
 
-        if cmd.status.success() {
-            Ok(output)
-        } else {
-            Err(output)
+        match status {
+            Some(status) if status.success() => Ok(output),
+            _ => Err(output),
         }
     }
 
-    pub fn state(&self) -> State {
-        let mut source_file =
-            File::open(&self.path).expect("We were unable to open the exercise file!");
-
-        let source = {
-            let mut s = String::new();
-            source_file
-                .read_to_string(&mut s)
-                .expect("We were unable to read the exercise file!");
-            s
-        };
+    // How long the compiled exercise may run before it's stopped
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
 
-        let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+    // How many points solving the exercise is worth, one unless specified
+    pub fn points(&self) -> u32 {
+        self.points.unwrap_or(1)
+    }
 
-        if !re.is_match(&source) {
-            return State::Done;
+    // The category of the exercise, which is the directory it lives in,
+    // e.g. `variables` for `exercises/variables/variables1.rs`.
+    // Exercises at the top of `exercises/` are grouped by their name
+    // without the trailing number, e.g. `quiz` for `exercises/quiz1.rs`.
+    pub fn category(&self) -> String {
+        let dir = self
+            .path
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|dir| dir.to_str())
+            .filter(|&dir| dir != "exercises");
+        match dir {
+            Some(dir) => dir.to_string(),
+            None => self
+                .path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default()
+                .trim_end_matches(|c: char| c.is_ascii_digit())
+                .to_string(),
         }
+    }
 
-        let matched_line_index = source
-            .lines()
-            .enumerate()
-            .find_map(|(i, line)| if re.is_match(line) { Some(i) } else { None })
-            .expect("This should not happen at all");
+    pub fn state(&self) -> State {
+        // The comment can be in any source file of a Cargo exercise
+        let sources = match self.mode {
+            Mode::Cargo => self
+                .files()
+                .into_iter()
+                .filter(|file| file.extension().is_some_and(|ext| ext == "rs"))
+                .collect(),
+            _ => vec![self.path.clone()],
+        };
+        sources
+            .iter()
+            .find_map(|path| pending_context(path))
+            .map_or(State::Done, State::Pending)
+    }
 
-        let min_line = ((matched_line_index as i32) - (CONTEXT as i32)).max(0) as usize;
-        let max_line = matched_line_index + CONTEXT;
+    // The files the user edits to solve the exercise: its source, along with
+    // the build script of a BuildScript exercise, or every file of the package
+    // of a Cargo exercise, leaving out hidden files and whatever cargo wrote
+    pub fn files(&self) -> Vec<PathBuf> {
+        match self.mode {
+            Mode::BuildScript => vec![self.path.clone(), self.build_script()],
+            Mode::Cargo => package_files(&self.path),
+            Mode::Compile | Mode::Test | Mode::Clippy => vec![self.path.clone()],
+        }
+    }
 
-        let context = source
-            .lines()
-            .enumerate()
-            .filter(|&(i, _)| i >= min_line && i <= max_line)
-            .map(|(i, line)| ContextLine {
-                line: line.to_string(),
-                number: i + 1,
-                important: i == matched_line_index,
-            })
-            .collect();
+    // The files the exercise is checked against, which the user doesn't edit:
+    // the golden files of its expected outputs and its grader
+    pub fn check_files(&self) -> Vec<PathBuf> {
+        self.expected_output
+            .iter()
+            .chain(self.cases.iter().map(|case| &case.expected_output))
+            .filter_map(ExpectedOutput::file)
+            .map(Path::to_path_buf)
+            .chain(self.grader.clone())
+            .collect()
+    }
+
+    // The build script of a BuildScript exercise, next to the exercise itself
+    pub fn build_script(&self) -> PathBuf {
+        self.path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("build.rs")
+    }
 
-        State::Pending(context)
+    // Whether the exercise is tagged with the tag, ignoring case
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
     }
 
     // Check that the exercise looks to be solved using self.state()
     // This is not the best way to check since
     // the user can just remove the "I AM NOT DONE" string from the file
     // without actually having solved anything.
-    // The only other way to truly check this would to compile and run
-    // the exercise; which would be both costly and counterintuitive
+    // `ProgressState::is_done` also takes the last verification
+    // of the exercise into account, prefer it where possible
     pub fn looks_done(&self) -> bool {
         self.state() == State::Done
     }
@@ -288,28 +970,297 @@ impl Display for Exercise {
     }
 }
 
-#[inline]
-fn clean() {
-    let _ignored = remove_file(&temp_file());
+// The lines around the `I AM NOT DONE` comment of the source file, if it has one
+fn pending_context(path: &Path) -> Option<Vec<ContextLine>> {
+    let mut source_file = File::open(path).expect("We were unable to open the exercise file!");
+
+    let source = {
+        let mut s = String::new();
+        source_file
+            .read_to_string(&mut s)
+            .expect("We were unable to read the exercise file!");
+        s
+    };
+
+    let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+
+    if !re.is_match(&source) {
+        return None;
+    }
+
+    let matched_line_index = source
+        .lines()
+        .enumerate()
+        .find_map(|(i, line)| if re.is_match(line) { Some(i) } else { None })
+        .expect("This should not happen at all");
+
+    let min_line = ((matched_line_index as i32) - (CONTEXT as i32)).max(0) as usize;
+    let max_line = matched_line_index + CONTEXT;
+
+    let context = source
+        .lines()
+        .enumerate()
+        .filter(|&(i, _)| i >= min_line && i <= max_line)
+        .map(|(i, line)| ContextLine {
+            line: line.to_string(),
+            number: i + 1,
+            important: i == matched_line_index,
+        })
+        .collect();
+
+    Some(context)
+}
+
+// The names of the grader tests in the test harness, as listed by libtest
+fn grader_tests(harness: &Path) -> io::Result<Vec<String>> {
+    let output = Command::new(harness)
+        .args(["--list", "--format", "terse"])
+        .output()?;
+    let prefix = format!("{GRADER_MODULE}::");
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_suffix(": test"))
+        .filter(|name| name.starts_with(&prefix))
+        .map(str::to_string)
+        .collect())
+}
+
+// Every file of the package in the directory, leaving out hidden
+// files and directories along with what cargo writes, which is the
+// target directory and the Cargo.lock it creates when testing the package
+pub fn package_files(dir: &Path) -> Vec<PathBuf> {
+    let options = MatchOptions {
+        require_literal_leading_dot: true,
+        ..MatchOptions::new()
+    };
+    let Ok(paths) = glob_with(&dir.join("**").join("*").to_string_lossy(), options) else {
+        return Vec::new();
+    };
+    paths
+        .filter_map(Result::ok)
+        .filter(|path| {
+            path.is_file()
+                && path.file_name().is_none_or(|name| name != "Cargo.lock")
+                && !path
+                    .strip_prefix(dir)
+                    .unwrap_or(path)
+                    .components()
+                    .any(|c| c == Component::Normal("target".as_ref()))
+        })
+        .collect()
+}
+
+// Run the command to completion and collect its output, killing it once it
+// has been running for longer than `timeout`. The input, if any, is written
+// to its standard input, which is closed afterwards.
+// The exit status is `None` if the command had to be killed.
+fn output_with_timeout(
+    cmd: &mut Command,
+    timeout: Duration,
+    stage: Stage,
+    input: Option<&str>,
+) -> io::Result<(ExerciseOutput, Option<ExitStatus>)> {
+    let stdin = match input {
+        Some(_) => Stdio::piped(),
+        None => Stdio::null(),
+    };
+    let mut child = cmd
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Write the input and read both pipes while waiting, so that a chatty
+    // child doesn't block on a full pipe buffer
+    write_in_background(child.stdin.take(), input.unwrap_or_default().to_string());
+    let stdout = read_to_end_in_background(child.stdout.take());
+    let stderr = read_to_end_in_background(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if Instant::now() >= deadline {
+            let _ignored = child.kill();
+            child.wait()?;
+            break None;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let output = ExerciseOutput {
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+        stage,
+        exit_code: status.and_then(|status| status.code()),
+        timed_out: status.is_none(),
+        diagnostics: Vec::new(),
+        cases: Vec::new(),
+    };
+    Ok((output, status))
+}
+
+// A child that exits without reading all of its input closes
+// the pipe, so errors writing to it are ignored
+fn write_in_background(pipe: Option<impl Write + Send + 'static>, input: String) {
+    if let Some(mut pipe) = pipe {
+        thread::spawn(move || {
+            let _ignored = pipe.write_all(input.as_bytes());
+        });
+    }
+}
+
+fn read_to_end_in_background(
+    pipe: Option<impl Read + Send + 'static>,
+) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ignored = pipe.read_to_end(&mut buf);
+        }
+        String::from_utf8_lossy(&buf).to_string()
+    })
+}
+
+// Find the test harnesses in the output of `cargo test --no-run --message-format=json`,
+// to run in the directory of their package with the `cargo:rustc-env` variables of
+// its build script, since `cargo test` would set them for the test harness as well
+fn test_harnesses(messages: &str) -> Vec<Harness> {
+    let messages: Vec<serde_json::Value> = messages
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let build_script_env = |package_id: &serde_json::Value| -> Vec<(String, String)> {
+        messages
+            .iter()
+            .filter(|message| {
+                message["reason"] == "build-script-executed" && &message["package_id"] == package_id
+            })
+            .filter_map(|message| {
+                serde_json::from_value::<Vec<(String, String)>>(message["env"].clone()).ok()
+            })
+            .flatten()
+            .collect()
+    };
+    messages
+        .iter()
+        .filter(|message| {
+            message["reason"] == "compiler-artifact" && message["profile"]["test"] == true
+        })
+        .filter_map(|message| {
+            let binary = PathBuf::from(message["executable"].as_str()?);
+            let dir = message["manifest_path"]
+                .as_str()
+                .and_then(|manifest| Path::new(manifest).parent())
+                .map(Path::to_path_buf);
+            Some(Harness {
+                binary,
+                dir,
+                env: build_script_env(&message["package_id"]),
+            })
+        })
+        .collect()
+}
+
+// Resolve a path relative to the current directory,
+// so that it can be referenced from a scratch directory
+fn absolute(path: &Path) -> PathBuf {
+    env::current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf())
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::path::Path;
 
     #[test]
     fn test_clean() {
-        File::create(&temp_file()).unwrap();
         let exercise = Exercise {
             name: String::from("example"),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::from(""),
+            ..Default::default()
         };
         let compiled = exercise.compile().unwrap();
+        let dir = compiled._handle.dir.clone();
+        assert!(compiled._handle.binary().exists());
         drop(compiled);
-        assert!(!Path::new(&temp_file()).exists());
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_cargo_exercise() {
+        let mut exercise = Exercise {
+            name: "shapes".into(),
+            path: PathBuf::from("tests/fixture/cargo/shapes"),
+            mode: Mode::Cargo,
+            ..Default::default()
+        };
+        let files = exercise.files();
+        for file in ["Cargo.toml", "src/lib.rs", "src/square.rs", "tests/area.rs"] {
+            assert!(
+                files.contains(&exercise.path.join(file)),
+                "{file} is missing"
+            );
+        }
+        assert_eq!(exercise.state(), State::Done);
+
+        exercise.path = PathBuf::from("tests/fixture/cargo/broken");
+        assert!(matches!(exercise.state(), State::Pending(_)));
+    }
+
+    #[test]
+    fn test_package_files() {
+        let dir = env::temp_dir().join(format!("rustlings_package_{}", process::id()));
+        for file in [
+            "Cargo.toml",
+            "Cargo.lock",
+            "src/lib.rs",
+            "target/debug/lib",
+            ".hidden",
+        ] {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let mut files = package_files(&dir);
+        files.sort();
+        assert_eq!(files, [dir.join("Cargo.toml"), dir.join("src/lib.rs")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_harnesses_of_workspace() {
+        let messages = [
+            r#"{"reason":"build-script-executed","package_id":"a","env":[["KEY","value"]]}"#,
+            r#"{"reason":"compiler-artifact","package_id":"a","manifest_path":"/ws/a/Cargo.toml","profile":{"test":true},"executable":"/target/a-1"}"#,
+            r#"{"reason":"compiler-artifact","package_id":"b","manifest_path":"/ws/b/Cargo.toml","profile":{"test":false},"executable":null}"#,
+            r#"{"reason":"compiler-artifact","package_id":"b","manifest_path":"/ws/b/Cargo.toml","profile":{"test":true},"executable":"/target/b-2"}"#,
+        ]
+        .join("\n");
+        assert_eq!(
+            test_harnesses(&messages),
+            [
+                Harness {
+                    binary: "/target/a-1".into(),
+                    dir: Some("/ws/a".into()),
+                    env: vec![("KEY".into(), "value".into())],
+                },
+                Harness {
+                    binary: "/target/b-2".into(),
+                    dir: Some("/ws/b".into()),
+                    env: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scratch_dirs_are_unique() {
+        let first = FileHandle::new();
+        let second = FileHandle::new();
+        assert_ne!(first.dir, second.dir);
     }
 
     #[test]
@@ -318,7 +1269,7 @@ mod test {
             name: "pending_exercise".into(),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::new(),
+            ..Default::default()
         };
 
         let state = exercise.state();
@@ -359,7 +1310,7 @@ mod test {
             name: "finished_exercise".into(),
             path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::new(),
+            ..Default::default()
         };
 
         assert_eq!(exercise.state(), State::Done);
@@ -371,9 +1322,146 @@ mod test {
             name: "exercise_with_output".into(),
             path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
             mode: Mode::Test,
-            hint: String::new(),
+            ..Default::default()
         };
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
     }
+
+    #[test]
+    fn test_output_matching() {
+        let output = "Hello, world!  \n\n";
+        assert!(!OutputMatch::Exact.matches("Hello, world!\n", output));
+        assert!(OutputMatch::Trimmed.matches("Hello, world!", output));
+        assert!(!OutputMatch::Trimmed.matches("Hello, World!", output));
+        assert!(OutputMatch::Regex.matches(r"Hello, \w+!", output));
+        assert!(!OutputMatch::Regex.matches("Hello", output));
+    }
+
+    #[test]
+    fn test_exercise_with_cases() {
+        let case = |input: &str, output: &str| Case {
+            input: input.into(),
+            expected_output: ExpectedOutput::Inline(output.into()),
+        };
+        let exercise = Exercise {
+            name: "sum".into(),
+            path: PathBuf::from("tests/fixture/output/sum.rs"),
+            mode: Mode::Compile,
+            cases: vec![case("1\n2\n", "3"), case("4\n", "5"), case("", "0")],
+            ..Default::default()
+        };
+        let output = exercise.compile().unwrap().run().unwrap_err();
+        let passed: Vec<bool> = output.cases.iter().map(|case| case.passed).collect();
+        assert_eq!(passed, [true, false, true]);
+        assert_eq!(output.stage, Stage::Output);
+        assert_eq!(output.stdout, "4\n");
+    }
+
+    #[test]
+    fn test_exercise_with_grader() {
+        let exercise = Exercise {
+            name: "tampered".into(),
+            path: PathBuf::from("tests/fixture/grader/tampered.rs"),
+            mode: Mode::Test,
+            grader: Some(PathBuf::from("tests/fixture/grader/graders/tampered.rs")),
+            ..Default::default()
+        };
+        let compilation = exercise.compile_with_grader().unwrap();
+        let output = compilation.run().unwrap();
+        assert!(!output.stdout.contains("grader::"));
+        let grader = compilation.run_grader().unwrap().unwrap_err();
+        assert_eq!(grader.stage, Stage::Grader);
+        assert!(grader.stdout.contains("test grader::multiplies ... FAILED"));
+    }
+
+    #[test]
+    fn test_grader_fails_when_the_exercise_exits() {
+        let exercise = Exercise {
+            name: "exits".into(),
+            path: PathBuf::from("tests/fixture/grader/exits.rs"),
+            mode: Mode::Test,
+            grader: Some(PathBuf::from("tests/fixture/grader/graders/tampered.rs")),
+            ..Default::default()
+        };
+        let compilation = exercise.compile_with_grader().unwrap();
+        let grader = compilation.run_grader().unwrap().unwrap_err();
+        assert_eq!(grader.exit_code, Some(0));
+        assert!(grader
+            .stderr
+            .contains("These grader tests didn't pass: grader::multiplies"));
+    }
+
+    #[test]
+    fn test_grader_is_out_of_reach_of_the_exercise() {
+        // A grader using `assert_eq!` as it is, which the exercise redefines
+        let grader = env::temp_dir().join(format!("rustlings_grader_{}.rs", process::id()));
+        fs::write(
+            &grader,
+            "use super::*;\n\n#[test]\nfn multiplies() {\n    assert_eq!(multiply(3, 4), 12);\n}\n",
+        )
+        .unwrap();
+        let exercise = Exercise {
+            name: "sneaky".into(),
+            path: PathBuf::from("tests/fixture/grader/sneaky.rs"),
+            mode: Mode::Test,
+            grader: Some(grader.clone()),
+            ..Default::default()
+        };
+        let compilation = exercise.compile_with_grader().unwrap();
+        fs::remove_file(&grader).unwrap();
+        let output = compilation.run().unwrap();
+        assert!(output
+            .stdout
+            .contains("test tests::grader::multiplies ... ok"));
+        assert!(!output.stdout.contains("test grader::multiplies"));
+        let grader = compilation.run_grader().unwrap().unwrap_err();
+        assert!(grader.stdout.contains("test grader::multiplies ... FAILED"));
+        assert!(!grader.stdout.contains("tests::grader::multiplies"));
+    }
+
+    #[test]
+    fn test_category() {
+        let exercise = |path: &str| Exercise {
+            name: String::new(),
+            path: PathBuf::from(path),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        assert_eq!(
+            exercise("exercises/variables/variables1.rs").category(),
+            "variables"
+        );
+        assert_eq!(exercise("exercises/quiz1.rs").category(), "quiz");
+    }
+
+    #[test]
+    fn test_compile_error_diagnostics() {
+        let exercise = Exercise {
+            name: "compFailure".into(),
+            path: PathBuf::from("tests/fixture/failure/compFailure.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let out = exercise.compile().err().unwrap();
+        assert_eq!(out.stage, Stage::Compile);
+        let diagnostic = out.diagnostics.iter().find(|d| d.is_relevant()).unwrap();
+        let span = diagnostic.span.as_ref().unwrap();
+        assert_eq!(span.file, "tests/fixture/failure/compFailure.rs");
+        assert_eq!((span.line, span.column), (3, 1));
+        assert!(out.stderr.contains("expected pattern"));
+    }
+
+    #[test]
+    fn test_exercise_timeout() {
+        let exercise = Exercise {
+            name: "timeout".into(),
+            path: PathBuf::from("tests/fixture/failure/timeout.rs"),
+            mode: Mode::Compile,
+            timeout: Some(1),
+            ..Default::default()
+        };
+        let out = exercise.compile().unwrap().run().unwrap_err();
+        assert!(out.timed_out);
+    }
 }
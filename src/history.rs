@@ -0,0 +1,182 @@
+use crate::exercise::Exercise;
+use console::style;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Where the attempts at every exercise are kept
+const HISTORY_DIR: &str = ".rustlings/history";
+
+// The source of an exercise, as the contents of each of its files
+pub type Sources = BTreeMap<PathBuf, String>;
+
+// An attempt at solving an exercise, whose sources are kept next to the list of attempts
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Attempt {
+    // Attempts are numbered from 1 in the order they were made
+    pub number: usize,
+    // When the attempt was verified, in seconds since the Unix epoch
+    pub timestamp: u64,
+    // Whether the attempt compiled and passed its checks
+    pub passed: bool,
+}
+
+fn history_dir(exercise: &Exercise) -> PathBuf {
+    Path::new(HISTORY_DIR).join(&exercise.name)
+}
+
+fn attempts_file(exercise: &Exercise) -> PathBuf {
+    history_dir(exercise).join("attempts.json")
+}
+
+fn sources_file(exercise: &Exercise, number: usize) -> PathBuf {
+    history_dir(exercise).join(format!("{number}.json"))
+}
+
+// The attempts at the exercise, from the first to the last one
+pub fn attempts(exercise: &Exercise) -> Vec<Attempt> {
+    fs::read_to_string(attempts_file(exercise))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+// The sources of the attempt
+pub fn sources(exercise: &Exercise, attempt: &Attempt) -> io::Result<Sources> {
+    let json = fs::read_to_string(sources_file(exercise, attempt.number))?;
+    serde_json::from_str(&json).map_err(io::Error::from)
+}
+
+// The sources of the exercise as they are now, which are all of its files
+// for an exercise that is a whole package
+fn current_sources(exercise: &Exercise) -> io::Result<Sources> {
+    exercise
+        .files()
+        .into_iter()
+        .map(|file| Ok((file.clone(), fs::read_to_string(file)?)))
+        .collect()
+}
+
+// Keep the current sources of the exercise as a new attempt, unless they're
+// the same as the last one. Failing to do so only leaves a gap in the
+// history, so errors are ignored.
+pub fn record(exercise: &Exercise, passed: bool) {
+    let _ = try_record(exercise, passed);
+}
+
+fn try_record(exercise: &Exercise, passed: bool) -> io::Result<()> {
+    let sources = current_sources(exercise)?;
+    let mut attempts = attempts(exercise);
+    if let Some(last) = attempts.last() {
+        if last.passed == passed && self::sources(exercise, last).is_ok_and(|s| s == sources) {
+            return Ok(());
+        }
+    }
+
+    let attempt = Attempt {
+        number: attempts.last().map_or(1, |last| last.number + 1),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default(),
+        passed,
+    };
+    fs::create_dir_all(history_dir(exercise))?;
+    fs::write(
+        sources_file(exercise, attempt.number),
+        serde_json::to_string_pretty(&sources).unwrap(),
+    )?;
+    attempts.push(attempt);
+    fs::write(
+        attempts_file(exercise),
+        serde_json::to_string_pretty(&attempts).unwrap(),
+    )
+}
+
+// Print the changes from the original exercise to the attempt, as a unified
+// diff of every file that changed. Files only one of them has are compared
+// with an empty one.
+pub fn print_diff(original: &Sources, attempt: &Sources, attempt_name: &str) {
+    let files: BTreeSet<&PathBuf> = original.keys().chain(attempt.keys()).collect();
+    let mut changed = false;
+    for file in files {
+        let before = original.get(file).map_or("", String::as_str);
+        let after = attempt.get(file).map_or("", String::as_str);
+        if before != after {
+            changed = true;
+            println!("{}", style(file.display()).bold());
+            print_text_diff(before, after);
+        }
+    }
+    if !changed {
+        println!("{attempt_name} is the same as the original exercise.");
+    }
+}
+
+// Print the changes from one text to the other, as a unified diff
+pub fn print_text_diff(original: &str, changed: &str) {
+    let diff = TextDiff::from_lines(original, changed);
+    for group in diff.grouped_ops(3) {
+        let (first, last) = (group[0], group[group.len() - 1]);
+        println!(
+            "{}",
+            style(format!(
+                "@@ -{},{} +{},{} @@",
+                first.old_range().start + 1,
+                last.old_range().end - first.old_range().start,
+                first.new_range().start + 1,
+                last.new_range().end - first.new_range().start,
+            ))
+            .cyan()
+        );
+        for op in group {
+            for change in diff.iter_changes(&op) {
+                let line = change.value().trim_end_matches('\n');
+                match change.tag() {
+                    ChangeTag::Delete => println!("{}", style(format!("-{line}")).red()),
+                    ChangeTag::Insert => println!("{}", style(format!("+{line}")).green()),
+                    ChangeTag::Equal => println!(" {line}"),
+                }
+            }
+        }
+    }
+}
+
+// Format the timestamp like "2023-05-17 14:03:09 UTC"
+pub fn format_timestamp(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86400, timestamp % 86400);
+    // Converts the number of days since 1970-01-01 to a date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_timestamp(951782400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(format_timestamp(1684332189), "2023-05-17 14:03:09 UTC");
+    }
+}
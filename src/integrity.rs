@@ -0,0 +1,594 @@
+use crate::exercise::{Exercise, Mode};
+use regex::Regex;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// Matches a function along with the attributes before it
+const FN_REGEX: &str = r"((?:#\[[^\[\]]*(?:\[[^\[\]]*\][^\[\]]*)*\]\s*)+)(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|async|unsafe)\s+)*fn\s+(\w+)";
+// Matches an inline module along with the attributes before it
+const MOD_REGEX: &str =
+    r"((?:#\[[^\[\]]*(?:\[[^\[\]]*\][^\[\]]*)*\]\s*)*)(?:pub(?:\([^)]*\))?\s+)?\bmod\s+\w+\s*\{";
+// Matches an attribute, inner or outer, capturing what's between the brackets
+const ATTRIBUTE_REGEX: &str = r"#!?\[([^\[\]]*(?:\[[^\[\]]*\][^\[\]]*)*)\]";
+// Matches the inner attributes at the start of a file or module
+const INNER_ATTRIBUTES_REGEX: &str = r"\A(?:\s*#!\[[^\[\]]*(?:\[[^\[\]]*\][^\[\]]*)*\])*";
+// Matches the start of an assertion, up to its opening delimiter
+const ASSERTION_REGEX: &str = r"\b(?:debug_)?assert(?:_eq|_ne)?!\s*[(\[{]";
+// The attributes that make a test pass without checking anything, or leave
+// it out of the test harness altogether
+const SUSPICIOUS_ATTRIBUTES: [&str; 4] = ["ignore", "should_panic", "cfg", "cfg_attr"];
+
+fn regexes() -> &'static [Regex; 5] {
+    static REGEXES: OnceLock<[Regex; 5]> = OnceLock::new();
+    REGEXES.get_or_init(|| {
+        [
+            Regex::new(FN_REGEX).unwrap(),
+            Regex::new(MOD_REGEX).unwrap(),
+            Regex::new(ATTRIBUTE_REGEX).unwrap(),
+            Regex::new(INNER_ATTRIBUTES_REGEX).unwrap(),
+            Regex::new(ASSERTION_REGEX).unwrap(),
+        ]
+    })
+}
+
+// A way the tests of an exercise differ from the original ones
+#[derive(PartialEq, Debug)]
+pub enum Tampering {
+    // The test isn't there anymore
+    Removed(String),
+    // The test asserts something else than it used to
+    ChangedAssertions(String),
+    // The test got an attribute like `#[ignore]` it didn't have
+    AddedAttribute { test: String, attribute: String },
+    // A module the test is in got an attribute like `#[cfg(any())]` it didn't have
+    AddedModuleAttribute { test: String, attribute: String },
+    // The test harness ran fewer tests than the original exercise has
+    SkippedTests { expected: usize, ran: usize },
+}
+
+impl Display for Tampering {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Tampering::Removed(test) => write!(f, "the test `{test}` was removed"),
+            Tampering::ChangedAssertions(test) => {
+                write!(f, "the assertions of the test `{test}` were changed")
+            }
+            Tampering::AddedAttribute { test, attribute } => {
+                write!(f, "`#[{attribute}]` was added to the test `{test}`")
+            }
+            Tampering::AddedModuleAttribute { test, attribute } => {
+                write!(
+                    f,
+                    "`#[{attribute}]` was added to the module of the test `{test}`"
+                )
+            }
+            Tampering::SkippedTests { expected, ran } => {
+                write!(f, "only {ran} of the {expected} tests ran")
+            }
+        }
+    }
+}
+
+// A `#[test]` function, reduced to what decides whether it passes
+#[derive(PartialEq, Debug)]
+struct TestFn {
+    name: String,
+    // The attributes without whitespace, like `should_panic(expected="..")`
+    attributes: Vec<String>,
+    // The attributes of the file and the modules the test is in
+    module_attributes: Vec<String>,
+    // The assertions in the order they're made, without whitespace
+    assertions: Vec<String>,
+}
+
+// How the tests in the files of the exercise differ from the ones in their
+// original versions, which are looked up in `pristine_dir` under the same
+// paths. Files without an original version aren't checked.
+pub fn check(exercise: &Exercise, pristine_dir: &Path) -> Vec<Tampering> {
+    exercise
+        .files()
+        .iter()
+        .filter(|file| file.extension().is_some_and(|ext| ext == "rs"))
+        .filter_map(|file| {
+            let original = fs::read_to_string(pristine_dir.join(file)).ok()?;
+            let submitted = fs::read_to_string(file).unwrap_or_default();
+            Some(compare(&original, &submitted))
+        })
+        .flatten()
+        .collect()
+}
+
+// Whether a test exercise that passed ran fewer tests than there are in the
+// original versions of its files, which happens when tests are left out of
+// the harness in a way the comparison of the tests doesn't catch.
+// `ran` is the number of tests in the output of the exercise.
+pub fn check_tests_run(exercise: &Exercise, pristine_dir: &Path, ran: usize) -> Option<Tampering> {
+    if !matches!(exercise.mode, Mode::Test | Mode::Cargo) {
+        return None;
+    }
+    let expected = exercise
+        .files()
+        .iter()
+        .filter(|file| file.extension().is_some_and(|ext| ext == "rs"))
+        .filter_map(|file| fs::read_to_string(pristine_dir.join(file)).ok())
+        .map(|original| test_fns(&original).len())
+        .sum();
+    (ran < expected).then_some(Tampering::SkippedTests { expected, ran })
+}
+
+// How the tests of the submitted source differ from the ones of the original.
+// Tests that were added aren't a problem, nor are changes outside of the
+// assertions, like filling in the blanks an exercise leaves in its tests, or
+// assertions the exercise leaves blank for the user to write.
+pub fn compare(original: &str, submitted: &str) -> Vec<Tampering> {
+    let submitted = test_fns(submitted);
+    let mut tamperings = Vec::new();
+    for test in test_fns(original) {
+        let Some(attempt) = submitted.iter().find(|attempt| attempt.name == test.name) else {
+            tamperings.push(Tampering::Removed(test.name));
+            continue;
+        };
+        let changed = attempt.assertions.len() != test.assertions.len()
+            || test
+                .assertions
+                .iter()
+                .zip(&attempt.assertions)
+                .any(|(original, attempt)| !is_blank(original) && original != attempt);
+        if changed {
+            tamperings.push(Tampering::ChangedAssertions(test.name.clone()));
+        }
+        for attribute in added_attributes(&test.attributes, &attempt.attributes) {
+            // A test without assertions only checks that the code doesn't
+            // panic, and exercises like tests4 are solved by making it check
+            // that the code does panic instead
+            if attribute_name(&attribute) == "should_panic" && test.assertions.is_empty() {
+                continue;
+            }
+            tamperings.push(Tampering::AddedAttribute {
+                test: test.name.clone(),
+                attribute,
+            });
+        }
+        for attribute in added_attributes(&test.module_attributes, &attempt.module_attributes) {
+            tamperings.push(Tampering::AddedModuleAttribute {
+                test: test.name.clone(),
+                attribute,
+            });
+        }
+    }
+    tamperings
+}
+
+// Whether the assertion is left for the user to write, like `assert!()` or
+// `assert_eq!(???, 10)`
+fn is_blank(assertion: &str) -> bool {
+    assertion.contains("???")
+        || ["!()", "![]", "!{}"]
+            .iter()
+            .any(|empty| assertion.ends_with(empty))
+}
+
+// The suspicious attributes among `submitted` that aren't in `original`
+fn added_attributes(original: &[String], submitted: &[String]) -> Vec<String> {
+    submitted
+        .iter()
+        .filter(|attribute| SUSPICIOUS_ATTRIBUTES.contains(&attribute_name(attribute)))
+        .filter(|attribute| !original.contains(attribute))
+        .cloned()
+        .collect()
+}
+
+// The name of an attribute, like `should_panic` in `should_panic(expected="..")`
+fn attribute_name(attribute: &str) -> &str {
+    let end = attribute
+        .find(|c: char| !c.is_alphanumeric() && c != '_' && c != ':')
+        .unwrap_or(attribute.len());
+    attribute[..end].rsplit("::").next().unwrap_or_default()
+}
+
+// The attributes in `source` without whitespace
+fn attributes(source: &str) -> Vec<String> {
+    let [_, _, attribute_regex, _, _] = regexes();
+    attribute_regex
+        .captures_iter(source)
+        .map(|attribute| without_whitespace(&attribute[1]))
+        .collect()
+}
+
+fn without_whitespace(source: &str) -> String {
+    source.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+// The `#[test]` functions of the source, wherever they are.
+// Comments are left out, so commented out code doesn't count.
+fn test_fns(source: &str) -> Vec<TestFn> {
+    let source = &without_comments(source);
+    let [fn_regex, mod_regex, _, inner_attributes_regex, assertion_regex] = regexes();
+    // The span of every inline module, with its outer and inner attributes
+    let modules: Vec<(usize, usize, Vec<String>)> = mod_regex
+        .captures_iter(source)
+        .map(|captures| {
+            let whole = captures.get(0).unwrap();
+            let open = whole.end() - 1;
+            let inner = inner_attributes_regex
+                .find(&source[whole.end()..])
+                .map_or("", |inner| inner.as_str());
+            let mut found = attributes(&captures[1]);
+            found.extend(attributes(inner));
+            (whole.start(), block_end(source, open), found)
+        })
+        .collect();
+    let file_attributes = inner_attributes_regex
+        .find(source)
+        .map_or(Vec::new(), |inner| attributes(inner.as_str()));
+    let mut tests = Vec::new();
+    for captures in fn_regex.captures_iter(source) {
+        let attributes = attributes(&captures[1]);
+        if !attributes.iter().any(|a| attribute_name(a) == "test") {
+            continue;
+        }
+        let start = captures.get(0).unwrap().start();
+        let mut module_attributes = file_attributes.clone();
+        for (_, _, attributes) in modules
+            .iter()
+            .filter(|(module_start, module_end, _)| (*module_start..*module_end).contains(&start))
+        {
+            module_attributes.extend(attributes.iter().cloned());
+        }
+        let after = captures.get(0).unwrap().end();
+        let body = match source[after..].find('{') {
+            Some(open) => &source[after + open..after + block_end(&source[after..], open)],
+            None => "",
+        };
+        let assertions = assertion_regex
+            .find_iter(body)
+            .map(|assertion| {
+                let end = block_end(body, assertion.end() - 1);
+                without_whitespace(&body[assertion.start()..end])
+            })
+            .collect();
+        tests.push(TestFn {
+            name: captures[2].to_string(),
+            attributes,
+            module_attributes,
+            assertions,
+        });
+    }
+    tests
+}
+
+// The source with every comment replaced by spaces, keeping the line breaks
+// and leaving string and character literals alone
+fn without_comments(source: &str) -> String {
+    let bytes = source.as_bytes();
+    let mut kept = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let comment_end = match (bytes[i], bytes.get(i + 1)) {
+            (b'/', Some(b'/')) => bytes[i..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(bytes.len(), |newline| i + newline),
+            (b'/', Some(b'*')) => {
+                // Block comments nest
+                let mut depth = 0;
+                let mut j = i;
+                while j < bytes.len() {
+                    match (bytes[j], bytes.get(j + 1)) {
+                        (b'/', Some(b'*')) => {
+                            depth += 1;
+                            j += 1;
+                        }
+                        (b'*', Some(b'/')) => {
+                            depth -= 1;
+                            j += 1;
+                            if depth == 0 {
+                                j += 1;
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    j += 1;
+                }
+                j.min(bytes.len())
+            }
+            (b'"' | b'\'', _) | (b'r', Some(b'"' | b'#')) => {
+                let end = literal_end(bytes, i);
+                kept.extend_from_slice(&bytes[i..end]);
+                i = end;
+                continue;
+            }
+            _ => {
+                kept.push(bytes[i]);
+                i += 1;
+                continue;
+            }
+        };
+        kept.extend(
+            bytes[i..comment_end]
+                .iter()
+                .map(|&b| if b == b'\n' { b'\n' } else { b' ' }),
+        );
+        i = comment_end;
+    }
+    // Every byte of a character in a comment was replaced, so this is still valid UTF-8
+    String::from_utf8(kept).unwrap_or_default()
+}
+
+// The index just past the string or character literal starting at `start`,
+// or `start + 1` if it turns out not to be one, like a lifetime or the `r`
+// of an identifier
+fn literal_end(bytes: &[u8], start: usize) -> usize {
+    match bytes[start] {
+        b'r' => {
+            if start > 0
+                && (bytes[start - 1].is_ascii_alphanumeric() || bytes[start - 1] == b'_')
+                && bytes[start - 1] != b'b'
+            {
+                return start + 1;
+            }
+            let hashes = bytes[start + 1..]
+                .iter()
+                .take_while(|&&b| b == b'#')
+                .count();
+            let quote = start + 1 + hashes;
+            if bytes.get(quote) != Some(&b'"') {
+                return start + 1;
+            }
+            let closing: Vec<u8> = std::iter::once(b'"')
+                .chain(std::iter::repeat_n(b'#', hashes))
+                .collect();
+            bytes[quote + 1..]
+                .windows(closing.len())
+                .position(|window| window == closing)
+                .map_or(bytes.len(), |end| quote + 1 + end + closing.len())
+        }
+        b'\'' if bytes.get(start + 1) == Some(&b'\\') => bytes[bytes.len().min(start + 3)..]
+            .iter()
+            .position(|&b| b == b'\'')
+            .map_or(bytes.len(), |end| start + 4 + end),
+        b'\'' => {
+            // A character literal, or a lifetime
+            let text = String::from_utf8_lossy(&bytes[start + 1..bytes.len().min(start + 6)]);
+            match text.chars().next() {
+                Some(c) if bytes.get(start + 1 + c.len_utf8()) == Some(&b'\'') => {
+                    start + 2 + c.len_utf8()
+                }
+                _ => start + 1,
+            }
+        }
+        _ => {
+            let mut i = start + 1;
+            while i < bytes.len() && bytes[i] != b'"' {
+                if bytes[i] == b'\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            (i + 1).min(bytes.len())
+        }
+    }
+}
+
+// The index just past the block whose opening delimiter is at `open`,
+// skipping over string and character literals.
+// This is the end of the source if the block is never closed.
+fn block_end(source: &str, open: usize) -> usize {
+    let bytes = source.as_bytes();
+    let (opening, closing) = match bytes[open] {
+        b'(' => (b'(', b')'),
+        b'[' => (b'[', b']'),
+        _ => (b'{', b'}'),
+    };
+    let mut depth = 0;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' | b'r' => {
+                i = literal_end(bytes, i);
+                continue;
+            }
+            c if c == opening => depth += 1,
+            c if c == closing => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ORIGINAL: &str = r#"
+fn double(n: i32) -> i32 {
+    n * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles() {
+        let n = ???;
+        assert_eq!(double(n), 4, "{} isn't doubled", '{');
+    }
+
+    #[test]
+    fn doubles_zero() {
+        assert_eq!(double(0), 0);
+    }
+
+    #[test]
+    fn doubles_negative_numbers() {
+        assert!(double(-1) < 0);
+    }
+}
+"#;
+
+    #[test]
+    fn test_test_fns() {
+        let tests = test_fns(ORIGINAL);
+        let names: Vec<&str> = tests.iter().map(|test| test.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["doubles", "doubles_zero", "doubles_negative_numbers"]
+        );
+        assert_eq!(
+            tests[0].assertions,
+            [r#"assert_eq!(double(n),4,"{}isn'tdoubled",'{')"#]
+        );
+    }
+
+    #[test]
+    fn test_untouched_tests() {
+        let solved = ORIGINAL
+            .replace("n * 2", "n + n")
+            .replace("???", "2")
+            .replace(
+                "assert_eq!(double(0), 0);",
+                "assert_eq!(\n            double(0),\n            0\n        );",
+            );
+        assert_eq!(compare(ORIGINAL, &solved), []);
+    }
+
+    #[test]
+    fn test_tampered_tests() {
+        let tampered = ORIGINAL
+            .replace(
+                "assert_eq!(double(0), 0);",
+                "assert_eq!(double(0), double(0));",
+            )
+            .replace(
+                "    #[test]\n    fn doubles_negative_numbers",
+                "    #[test]\n    #[ignore]\n    fn doubles_negative_numbers",
+            )
+            .replace("    #[test]\n    fn doubles()", "    fn doubles()");
+        assert_eq!(
+            compare(ORIGINAL, &tampered),
+            [
+                Tampering::Removed("doubles".to_string()),
+                Tampering::ChangedAssertions("doubles_zero".to_string()),
+                Tampering::AddedAttribute {
+                    test: "doubles_negative_numbers".to_string(),
+                    attribute: "ignore".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hidden_tests() {
+        let tampered = ORIGINAL
+            .replace(
+                "#[cfg(test)]\nmod tests {",
+                "#[cfg(test)] mod tests {\n    #![cfg(any())]",
+            )
+            .replace(
+                "    #[test]\n    fn doubles_zero",
+                "    #[test] #[cfg_attr(all(), ignore)] fn doubles_zero",
+            )
+            .replace(
+                "assert!(double(-1) < 0);",
+                "/* assert!(double(-1) < 0); } */",
+            );
+        let module_attribute = |test: &str| Tampering::AddedModuleAttribute {
+            test: test.to_string(),
+            attribute: "cfg(any())".to_string(),
+        };
+        assert_eq!(
+            compare(ORIGINAL, &tampered),
+            [
+                module_attribute("doubles"),
+                Tampering::AddedAttribute {
+                    test: "doubles_zero".to_string(),
+                    attribute: "cfg_attr(all(),ignore)".to_string(),
+                },
+                module_attribute("doubles_zero"),
+                Tampering::ChangedAssertions("doubles_negative_numbers".to_string()),
+                module_attribute("doubles_negative_numbers"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filled_in_blanks() {
+        let original = r#"
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn you_can_assert() {
+        assert!();
+        assert_eq!(area(2), 4);
+    }
+
+    #[test]
+    fn correct_width_and_height() {
+        let rect = Rectangle::new(10, 20);
+        assert_eq!(???, 10);
+        assert_eq!(maybe_icecream(9), Some(???));
+    }
+
+    #[test]
+    fn negative_width() {
+        let _rect = Rectangle::new(-10, 10);
+    }
+
+    #[test]
+    fn doubles() {
+        assert_eq!(double(2), 4);
+    }
+}
+"#;
+        let solved = original
+            .replace("assert!();", "assert!(is_even(2));")
+            .replace("assert_eq!(???, 10);", "assert_eq!(rect.width, 10);")
+            .replace("Some(???)", "Some(5)")
+            .replace(
+                "    #[test]\n    fn negative_width",
+                "    #[test]\n    #[should_panic(expected = \"negative\")]\n    fn negative_width",
+            );
+        assert_eq!(compare(original, &solved), []);
+
+        // What the exercise gave stays checked
+        let tampered = solved
+            .replace("assert_eq!(area(2), 4);", "assert_eq!(area(2), area(2));")
+            .replace(
+                "    #[test]\n    fn doubles",
+                "    #[test]\n    #[should_panic]\n    fn doubles",
+            )
+            .replace("        assert_eq!(rect.width, 10);\n", "");
+        assert_eq!(
+            compare(original, &tampered),
+            [
+                Tampering::ChangedAssertions("you_can_assert".to_string()),
+                Tampering::ChangedAssertions("correct_width_and_height".to_string()),
+                Tampering::AddedAttribute {
+                    test: "doubles".to_string(),
+                    attribute: "should_panic".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commented_out_tests() {
+        let source = "// #[test]\n// fn one() {}\n/* #[test]\nfn two() {} */\n#[test]\nfn three() {\n    assert_eq!(\"/*\", r#\"/*\"#);\n}\n";
+        let tests = test_fns(source);
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].name, "three");
+        assert_eq!(tests[0].assertions, [r##"assert_eq!("/*",r#"/*"#)"##]);
+    }
+}
@@ -0,0 +1,175 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+// Matches the line libtest prints for every test it ran, like
+// `test tests::adds ... ok`, with its duration if `--report-time` is on.
+// Tests with `#[should_panic]` have `- should panic` after their name.
+const TEST_LINE_REGEX: &str = r"(?m)^test (\S+)(?: - should panic)? \.\.\. (ok|FAILED|ignored)(?:, [^<\r\n]*)?(?: <([\d.]+)s>)?\r?$";
+
+fn test_line_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(TEST_LINE_REGEX).unwrap())
+}
+
+// How a single test of a test harness went
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct TestResult {
+    pub name: String,
+    pub outcome: Outcome,
+    // What the test panicked with, if it failed
+    pub message: Option<String>,
+    // How long the test took, which libtest only reports with `--report-time`
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+// The results of the tests in the output of a test harness run with
+// `--show-output`, in the order they finished
+pub fn parse(stdout: &str) -> Vec<TestResult> {
+    test_line_regex()
+        .captures_iter(stdout)
+        .map(|captures| {
+            let name = captures[1].to_string();
+            let outcome = match &captures[2] {
+                "ok" => Outcome::Passed,
+                "ignored" => Outcome::Ignored,
+                _ => Outcome::Failed,
+            };
+            let message = match outcome {
+                Outcome::Failed => panic_message(stdout, &name),
+                Outcome::Passed | Outcome::Ignored => None,
+            };
+            let duration_ms = captures
+                .get(3)
+                .and_then(|seconds| seconds.as_str().parse::<f64>().ok())
+                .map(|seconds| (seconds * 1000.0).round() as u64);
+            TestResult {
+                name,
+                outcome,
+                message,
+                duration_ms,
+            }
+        })
+        .collect()
+}
+
+// What the failed test panicked with, taken from the output it captured,
+// which libtest prints under `---- name stdout ----` in the failures
+fn panic_message(stdout: &str, name: &str) -> Option<String> {
+    let heading = format!("---- {name} stdout ----");
+    let (_, captured) = stdout.rsplit_once(&heading)?;
+    let captured: Vec<&str> = captured
+        .lines()
+        .take_while(|line| !line.starts_with("---- ") && *line != "failures:")
+        .collect();
+    // The message follows the `thread 'name' panicked at file:line:column:` line,
+    // and is followed by a note on how to get a backtrace
+    let message: Vec<&str> = match captured
+        .iter()
+        .position(|line| line.contains("panicked at"))
+    {
+        Some(panicked) => captured[panicked + 1..]
+            .iter()
+            .copied()
+            .take_while(|line| !line.starts_with("note: "))
+            .collect(),
+        None => captured,
+    };
+    let message = message.join("\n").trim().to_string();
+    (!message.is_empty()).then_some(message)
+}
+
+// How many of the tests pass, like "4/6 tests passing"
+pub fn summary(tests: &[TestResult]) -> String {
+    let passed = tests
+        .iter()
+        .filter(|test| test.outcome == Outcome::Passed)
+        .count();
+    format!("{passed}/{} tests passing", tests.len())
+}
+
+// The names of the tests that failed
+pub fn failed(tests: &[TestResult]) -> Vec<&str> {
+    tests
+        .iter()
+        .filter(|test| test.outcome == Outcome::Failed)
+        .map(|test| test.name.as_str())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const OUTPUT: &str = "
+running 5 tests
+test tests::is_ignored ... ignored, not done yet
+test tests::adds ... ok
+test tests::subtracts ... FAILED
+test tests::multiplies ... ok <0.012s>
+test tests::divides_by_zero - should panic ... ok
+
+successes:
+
+---- tests::adds stdout ----
+adding
+
+successes:
+    tests::adds
+    tests::multiplies
+    tests::divides_by_zero
+
+failures:
+
+---- tests::subtracts stdout ----
+subtracting
+
+thread 'tests::subtracts' (1234) panicked at src/lib.rs:12:9:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    tests::subtracts
+
+test result: FAILED. 3 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+
+    #[test]
+    fn test_parse() {
+        let tests = parse(OUTPUT);
+        let outcomes: Vec<(&str, Outcome)> = tests
+            .iter()
+            .map(|test| (test.name.as_str(), test.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("tests::is_ignored", Outcome::Ignored),
+                ("tests::adds", Outcome::Passed),
+                ("tests::subtracts", Outcome::Failed),
+                ("tests::multiplies", Outcome::Passed),
+                ("tests::divides_by_zero", Outcome::Passed),
+            ]
+        );
+        assert_eq!(
+            tests[2].message.as_deref(),
+            Some("assertion `left == right` failed\n  left: 1\n right: 2")
+        );
+        assert_eq!(tests[1].message, None);
+        assert_eq!(tests[1].duration_ms, None);
+        assert_eq!(tests[3].duration_ms, Some(12));
+        assert_eq!(summary(&tests), "3/5 tests passing");
+        assert_eq!(failed(&tests), ["tests::subtracts"]);
+    }
+}
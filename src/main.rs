@@ -1,35 +1,53 @@
+use crate::cicv::{cicv_verify, default_jobs, git_user_name, original_exercises};
+use crate::deps::DependencyMap;
 use crate::exercise::{Exercise, ExerciseList};
+use crate::message::{Event, MessageFormat};
 use crate::project::RustAnalyzerProject;
-use crate::run::{reset, run};
+use crate::report::{write_report, ReportFormat};
+use crate::run::run;
+use crate::state::ProgressState;
+use crate::validate::validate;
 use crate::verify::verify;
+use crate::watcher::{ExerciseWatcher, WatchOptions};
 use argh::FromArgs;
-use console::Emoji;
-use notify::DebouncedEvent;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use serde::{Deserialize, Serialize};
-use std::ffi::OsStr;
+use console::{style, Emoji};
 use std::fs;
 use std::io::{self, prelude::*};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, RecvTimeoutError};
-use std::sync::{Arc, Mutex};
+use std::str::FromStr;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
 use std::thread;
 use std::time::Duration;
-use std::time::{UNIX_EPOCH, SystemTime};
 
 #[macro_use]
 mod ui;
 
+mod cache;
+mod cicv;
+mod deps;
+mod diagnostics;
 mod exercise;
+mod history;
+mod integrity;
+mod libtest;
+mod message;
+mod pristine;
 mod project;
+mod report;
 mod run;
+mod state;
+mod tui;
+mod validate;
 mod verify;
+mod watcher;
 
 // In sync with crate version
 const VERSION: &str = "5.5.1";
 
+// Where `cicvverify` writes its report, read by the classroom workflow
+const DEFAULT_REPORT_PATH: &str = ".github/result/check_result.json";
+
 #[derive(FromArgs, PartialEq, Debug)]
 /// Rustlings is a collection of small exercises to get you used to writing and reading Rust code
 struct Args {
@@ -39,6 +57,10 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// how to report progress: human (default) or json, which prints one event per line
+    /// for `verify`, `run`, `list` and `hint`
+    #[argh(option, default = "MessageFormat::Human")]
+    message_format: MessageFormat,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -50,20 +72,44 @@ enum Subcommands {
     Watch(WatchArgs),
     Run(RunArgs),
     Reset(ResetArgs),
+    Init(InitArgs),
     Hint(HintArgs),
     List(ListArgs),
     Lsp(LspArgs),
-    CicvVerify(CicvVerifyArgs)
+    Validate(ValidateArgs),
+    History(HistoryArgs),
+    Diff(DiffArgs),
+    CicvVerify(CicvVerifyArgs),
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "cicvverify", description = "cicvverify")]
-struct CicvVerifyArgs {}
+struct CicvVerifyArgs {
+    #[argh(option, short = 'j')]
+    /// the number of exercises to verify in parallel, defaults to the number of CPUs
+    jobs: Option<usize>,
+    #[argh(option)]
+    /// the name of the user to put in the report, defaults to git's user.name
+    user_name: Option<String>,
+    #[argh(option, short = 'o', default = "PathBuf::from(DEFAULT_REPORT_PATH)")]
+    /// where to write the report, defaults to .github/result/check_result.json
+    output: PathBuf,
+    #[argh(option, default = "ReportFormat::Json")]
+    /// the format of the report: json (default), junit, tap or markdown
+    format: ReportFormat,
+    #[argh(option)]
+    /// a checkout of the original exercises to check the tests against and take the graders from, neither happens without one
+    pristine: Option<PathBuf>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    /// verify every exercise, even the ones that passed before without changing since
+    #[argh(switch)]
+    force: bool,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
@@ -72,6 +118,15 @@ struct WatchArgs {
     /// show hints on success
     #[argh(switch)]
     success_hints: bool,
+    /// show a full screen UI with the exercises, the output and the hint
+    #[argh(switch)]
+    tui: bool,
+    /// how long files must stay unchanged before they're checked, in milliseconds (default: 1000)
+    #[argh(option, default = "1000")]
+    debounce: u64,
+    /// poll the files for changes, for filesystems that don't report them like network drives
+    #[argh(switch)]
+    poll: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -85,11 +140,26 @@ struct RunArgs {
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "reset")]
-/// Resets a single exercise using "git stash -- <filename>"
+/// Resets exercises to their original version, keeping a backup of your attempts
 struct ResetArgs {
     #[argh(positional)]
     /// the name of the exercise
-    name: String,
+    name: Option<String>,
+    #[argh(switch)]
+    /// reset all exercises
+    all: bool,
+    #[argh(option)]
+    /// reset the exercises of a category, like `variables`
+    category: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "init")]
+/// Keeps a copy of the exercises as they are now for `reset`, run it before changing them
+struct InitArgs {
+    #[argh(switch)]
+    /// replace the copies kept already instead of only copying the exercises that don't have one
+    force: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -101,12 +171,38 @@ struct HintArgs {
     name: String,
 }
 
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "history")]
+/// Lists your attempts at an exercise
+struct HistoryArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "diff")]
+/// Shows how an attempt at an exercise differs from the original exercise
+struct DiffArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: String,
+    #[argh(positional)]
+    /// the number of the attempt as shown by `history`, defaults to the last one
+    attempt: Option<usize>,
+}
+
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "lsp")]
 /// Enable rust-analyzer for exercises
 struct LspArgs {}
 
 #[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "validate")]
+/// Checks info.toml for mistakes, like exercises that don't exist
+struct ValidateArgs {}
+
+#[derive(FromArgs, PartialEq, Debug, Default)]
 #[argh(subcommand, name = "list")]
 /// Lists the exercises available in Rustlings
 struct ListArgs {
@@ -126,32 +222,40 @@ struct ListArgs {
     #[argh(switch, short = 's')]
     /// display only exercises that have been solved
     solved: bool,
+    #[argh(option, short = 't')]
+    /// display only exercises with this tag, can be given several times
+    tag: Vec<String>,
+    #[argh(option)]
+    /// display only exercises of at most this difficulty
+    max_difficulty: Option<u8>,
+    #[argh(option)]
+    /// sort the exercises by difficulty or time instead of the recommended order
+    sort: Option<ListOrder>,
 }
 
-#[derive(Deserialize, Serialize)]
-pub struct ExerciseCheckList {
-    pub exercises: Vec<ExerciseResult>,
-    pub user_name: Option<String>,
-    pub statistics: ExerciseStatistics
+// How `list` can sort the exercises
+#[derive(PartialEq, Debug)]
+enum ListOrder {
+    Difficulty,
+    Time,
 }
 
-#[derive(Deserialize, Serialize)]
-pub struct ExerciseResult {
-    pub name: String,
-    pub result: bool
-}
+impl FromStr for ListOrder {
+    type Err = String;
 
-#[derive(Deserialize, Serialize)]
-pub struct  ExerciseStatistics {
-    pub total_exercations: usize,
-    pub total_succeeds: usize,
-    pub total_failures: usize,
-    pub total_time: u32,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "difficulty" => Ok(ListOrder::Difficulty),
+            "time" => Ok(ListOrder::Time),
+            _ => Err(format!("unknown order `{s}`, expected difficulty or time")),
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() {
     let args: Args = argh::from_env();
+    message::set_format(args.message_format);
 
     if args.version {
         println!("v{VERSION}");
@@ -179,7 +283,29 @@ async fn main() {
     }
 
     let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+    let problems = validate(toml_str, Path::new("exercises"));
+    let validating = matches!(args.nested, Some(Subcommands::Validate(_)));
+    // Only errors keep the exercises from being loaded, warnings are left to `validate`
+    let errors: Vec<_> = problems.iter().filter(|p| p.is_error()).collect();
+    if !validating && !errors.is_empty() {
+        for problem in errors {
+            if message::is_json() {
+                message::emit(&Event::Error {
+                    message: problem.to_string(),
+                });
+            } else {
+                println!("{problem}");
+            }
+        }
+        if !message::is_json() {
+            println!("Run `rustlings validate` for more details.");
+        }
+        std::process::exit(1);
+    }
+    // The validation made sure that the exercises can be loaded, unless it's about to report why not
+    let exercises = toml::from_str::<ExerciseList>(toml_str)
+        .map(|list| list.exercises)
+        .unwrap_or_default();
     let verbose = args.nocapture;
 
     let command = args.nested.unwrap_or_else(|| {
@@ -188,58 +314,7 @@ async fn main() {
     });
     match command {
         Subcommands::List(subargs) => {
-            if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
-            }
-            let mut exercises_done: u16 = 0;
-            let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
-            exercises.iter().for_each(|e| {
-                let fname = format!("{}", e.path.display());
-                let filter_cond = filters
-                    .split(',')
-                    .filter(|f| !f.trim().is_empty())
-                    .any(|f| e.name.contains(&f) || fname.contains(&f));
-                let status = if e.looks_done() {
-                    exercises_done += 1;
-                    "Done"
-                } else {
-                    "Pending"
-                };
-                let solve_cond = {
-                    (e.looks_done() && subargs.solved)
-                        || (!e.looks_done() && subargs.unsolved)
-                        || (!subargs.solved && !subargs.unsolved)
-                };
-                if solve_cond && (filter_cond || subargs.filter.is_none()) {
-                    let line = if subargs.paths {
-                        format!("{fname}\n")
-                    } else if subargs.names {
-                        format!("{}\n", e.name)
-                    } else {
-                        format!("{:<17}\t{fname:<46}\t{status:<7}\n", e.name)
-                    };
-                    // Somehow using println! leads to the binary panicking
-                    // when its output is piped.
-                    // So, we're handling a Broken Pipe error and exiting with 0 anyway
-                    let stdout = std::io::stdout();
-                    {
-                        let mut handle = stdout.lock();
-                        handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
-                            match e.kind() {
-                                std::io::ErrorKind::BrokenPipe => std::process::exit(0),
-                                _ => std::process::exit(1),
-                            };
-                        });
-                    }
-                }
-            });
-            let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
-            println!(
-                "Progress: You completed {} / {} exercises ({:.1} %).",
-                exercises_done,
-                exercises.len(),
-                percentage_progress
-            );
+            list_exercises(&exercises, &subargs);
             std::process::exit(0);
         }
 
@@ -249,88 +324,167 @@ async fn main() {
         }
 
         Subcommands::Reset(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let to_reset: Vec<&Exercise> = if subargs.all {
+                exercises.iter().collect()
+            } else if let Some(category) = &subargs.category {
+                exercises
+                    .iter()
+                    .filter(|e| &e.category() == category)
+                    .collect()
+            } else if let Some(name) = &subargs.name {
+                vec![find_exercise(name, &exercises)]
+            } else {
+                println!("Which exercises should be reset? Give the name of an exercise, --category or --all.");
+                std::process::exit(1);
+            };
+            if to_reset.is_empty() {
+                println!("No exercises found to reset!");
+                std::process::exit(1);
+            }
 
-            reset(exercise).unwrap_or_else(|_| std::process::exit(1));
+            reset_exercises(&to_reset).unwrap_or_else(|_| std::process::exit(1));
         }
 
+        Subcommands::Init(subargs) => match pristine::snapshot(&exercises, subargs.force) {
+            Ok(copied) => println!(
+                "Kept a copy of {} exercises in {}, `rustlings reset` restores them from there.",
+                copied,
+                pristine::dir().display()
+            ),
+            Err(e) => {
+                println!("Failed to copy the exercises: {e}");
+                std::process::exit(1);
+            }
+        },
+
         Subcommands::Hint(subargs) => {
             let exercise = find_exercise(&subargs.name, &exercises);
 
-            println!("{}", exercise.hint);
+            print_next_hint(
+                exercise,
+                &format!("run `rustlings hint {}` again", exercise.name),
+            );
         }
 
-        Subcommands::Verify(_subargs) => {
-            verify(&exercises, (0, exercises.len()), verbose, false)
+        Subcommands::Verify(subargs) => {
+            verify(&exercises, (0, exercises.len()), verbose, false, subargs.force)
                 .unwrap_or_else(|_| std::process::exit(1));
         }
 
-        Subcommands::CicvVerify(_subargs) => {
-            // let toml_str = &fs::read_to_string("info.toml").unwrap();
-            // exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
-            let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-            let rights = Arc::new(Mutex::new(0));
-            let alls = exercises.len();
-
-            let exercise_check_list =  Arc::new(Mutex::new(
-                ExerciseCheckList {
-                    exercises: vec![], 
-                    user_name:  None, 
-                    statistics: ExerciseStatistics { 
-                        total_exercations: alls, 
-                        total_succeeds: 0, 
-                        total_failures: 0, 
-                        total_time: 0, 
+        Subcommands::CicvVerify(subargs) => {
+            let jobs = subargs.jobs.unwrap_or_else(default_jobs);
+            // The copies `rustlings init` keeps can't be trusted for grading,
+            // since they're taken from whatever the exercises are at the time
+            let pristine_dir = subargs.pristine.clone().filter(|dir| dir.is_dir());
+            if pristine_dir.is_none() {
+                let reason = match &subargs.pristine {
+                    Some(dir) => format!("There are no original exercises in {}", dir.display()),
+                    None => "No original exercises were given with --pristine".to_string(),
+                };
+                warn!("{}, so the tests of the exercises won't be checked for changes and their graders won't run", reason);
+            }
+            // Which exercises are graded and how is taken from the original exercises too,
+            // and graders only ever come from there
+            let exercises = match pristine_dir.as_deref().and_then(original_exercises) {
+                Some(originals) => originals,
+                None => {
+                    if let Some(dir) = &pristine_dir {
+                        warn!("There's no info.toml in {}, so the graders of the exercises won't run", dir.display());
                     }
+                    exercises
+                        .into_iter()
+                        .map(|exercise| Exercise {
+                            grader: None,
+                            ..exercise
+                        })
+                        .collect()
                 }
-            ));
-
-            let mut tasks = vec![];
-            for exercise in exercises {
-                let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                let inner_exercise = exercise;
-                let c_mutex = Arc::clone(&rights);
-                let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-                let _verbose = verbose.clone();
-                let t = tokio::task::spawn( async move {
-                    match run(&inner_exercise, true) {
-                    // match verify(vec![&inner_exercise], (0, 1), true, true) {
-                        Ok(_) => {
-                            *c_mutex.lock().unwrap() += 1;
-                            println!("{}执行成功", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: true,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_succeeds += 1;
-                        },
-                        Err(_) => {
-                            println!("{}执行失败", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: false,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_failures += 1;
-                        }
-                    }
-                });
-                tasks.push(t);
+            };
+            let mut check_list = cicv_verify(exercises, jobs, pristine_dir).await;
+            check_list.user_name = subargs.user_name.or_else(git_user_name);
+            println!(
+                "===============================试卷批改完成,总耗时: {:.1} s; ==================================",
+                check_list.statistics.total_time_ms as f64 / 1000.0
+            );
+            if let Err(e) = write_report(&check_list, &subargs.output, subargs.format) {
+                println!(
+                    "Failed to write the report to {}: {e}",
+                    subargs.output.display()
+                );
+                std::process::exit(1);
             }
-            for task in tasks { task.await.unwrap(); }
-            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-            let total_time = now_end - now_start;
-            println!("===============================试卷批改完成,总耗时: {} s; ==================================", total_time);
-            let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-            exercise_check_list_ref.lock().unwrap().statistics.total_time = total_time as u32;
-            let serialized = serde_json::to_string_pretty(&*exercise_check_list.lock().unwrap()).unwrap();
-            fs::write(".github/result/check_result.json", serialized).unwrap();
-        },
+            if check_list.statistics.total_failures > 0 {
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::History(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises);
+            let attempts = history::attempts(exercise);
+            if attempts.is_empty() {
+                println!("There are no attempts at {} yet.", exercise.name);
+            }
+            for attempt in attempts {
+                let result = if attempt.passed {
+                    style("passed").green()
+                } else {
+                    style("failed").red()
+                };
+                println!(
+                    "#{:<3} {}  {}",
+                    attempt.number,
+                    history::format_timestamp(attempt.timestamp),
+                    result
+                );
+            }
+        }
+
+        Subcommands::Diff(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises);
+            let attempts = history::attempts(exercise);
+            let attempt = match subargs.attempt {
+                Some(number) => attempts.iter().find(|a| a.number == number),
+                None => attempts.last(),
+            };
+            let Some(attempt) = attempt else {
+                println!(
+                    "No such attempt at {0}, see `rustlings history {0}`.",
+                    exercise.name
+                );
+                std::process::exit(1);
+            };
+            match (pristine::original(exercise), history::sources(exercise, attempt)) {
+                (Ok(original), Ok(source)) => history::print_diff(
+                    &original,
+                    &source,
+                    &format!("Attempt #{}", attempt.number),
+                ),
+                (Err(e), _) if e.kind() == io::ErrorKind::NotFound => {
+                    println!(
+                        "There is no original version of {exercise} to compare with, `rustlings init` keeps one before you change the exercises."
+                    );
+                    std::process::exit(1);
+                }
+                (Err(e), _) | (_, Err(e)) => {
+                    println!("Failed to read the attempt or the original exercise: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Subcommands::Validate(_subargs) => {
+            for problem in &problems {
+                println!("{problem}");
+            }
+            let errors = problems.iter().filter(|p| p.is_error()).count();
+            println!(
+                "Found {errors} error(s) and {} warning(s) in info.toml.",
+                problems.len() - errors
+            );
+            if errors > 0 {
+                std::process::exit(1);
+            }
+        }
 
         Subcommands::Lsp(_subargs) => {
             let mut project = RustAnalyzerProject::new();
@@ -351,69 +505,238 @@ async fn main() {
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose, _subargs.success_hints) {
-            Err(e) => {
-                println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
-                );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
-                std::process::exit(1);
+        Subcommands::Watch(subargs) => {
+            let options = WatchOptions {
+                debounce: Duration::from_millis(subargs.debounce),
+                poll: subargs.poll,
+            };
+            let status = if subargs.tui {
+                tui::watch(&exercises, &options)
+            } else {
+                watch(&exercises, &options, verbose, subargs.success_hints)
+            };
+            match status {
+                Err(e) => {
+                    println!(
+                        "Error: Could not watch your progress. Error message was {:?}.",
+                        e
+                    );
+                    println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+                    std::process::exit(1);
+                }
+                Ok(WatchStatus::Finished) => {
+                    println!(
+                        "{emoji} All exercises completed! {emoji}",
+                        emoji = Emoji("🎉", "★")
+                    );
+                    println!("\n{FENISH_LINE}\n");
+                }
+                Ok(WatchStatus::Unfinished) => {
+                    println!("We hope you're enjoying learning about Rust!");
+                    println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+                }
             }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
+        }
+    }
+}
+
+// List the exercises the way the `list` subcommand does
+fn list_exercises(exercises: &[Exercise], subargs: &ListArgs) {
+    if !subargs.paths && !subargs.names && !message::is_json() {
+        println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
+    }
+    let mut exercises_done: u16 = 0;
+    let progress = ProgressState::load();
+    let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
+    let mut listed: Vec<&Exercise> = exercises.iter().collect();
+    // Exercises without the metadata to sort on go last
+    match subargs.sort {
+        Some(ListOrder::Difficulty) => {
+            listed.sort_by_key(|e| e.difficulty.unwrap_or(u8::MAX))
+        }
+        Some(ListOrder::Time) => {
+            listed.sort_by_key(|e| e.estimated_minutes.unwrap_or(u32::MAX))
+        }
+        None => {}
+    }
+    listed.into_iter().for_each(|e| {
+        let fname = format!("{}", e.path.display());
+        let filter_cond = filters
+            .split(',')
+            .filter(|f| !f.trim().is_empty())
+            .any(|f| e.name.contains(f) || fname.contains(f));
+        let done = progress.is_done(e);
+        let status = if done {
+            exercises_done += 1;
+            "Done"
+        } else {
+            "Pending"
+        };
+        let solve_cond = {
+            (done && subargs.solved)
+                || (!done && subargs.unsolved)
+                || (!subargs.solved && !subargs.unsolved)
+        };
+        let metadata_cond = subargs.tag.iter().all(|tag| e.has_tag(tag))
+            && subargs
+                .max_difficulty
+                .is_none_or(|max| e.difficulty.is_some_and(|d| d <= max));
+        if solve_cond && metadata_cond && (filter_cond || subargs.filter.is_none()) {
+            if message::is_json() {
+                message::emit(&Event::Exercise {
+                    name: &e.name,
+                    path: &e.path,
+                    mode: e.mode,
+                    done,
+                    difficulty: e.difficulty,
+                    tags: &e.tags,
+                    requires: &e.requires,
+                    estimated_minutes: e.estimated_minutes,
+                });
+                return;
+            }
+            let line = if subargs.paths {
+                format!("{fname}\n")
+            } else if subargs.names {
+                format!("{}\n", e.name)
+            } else {
+                format!("{:<17}\t{fname:<46}\t{status:<7}\n", e.name)
+            };
+            // Somehow using println! leads to the binary panicking
+            // when its output is piped.
+            // So, we're handling a Broken Pipe error and exiting with 0 anyway
+            let stdout = std::io::stdout();
+            {
+                let mut handle = stdout.lock();
+                handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
+                    match e.kind() {
+                        std::io::ErrorKind::BrokenPipe => std::process::exit(0),
+                        _ => std::process::exit(1),
+                    };
+                });
+            }
+        }
+    });
+    if message::is_json() {
+        message::emit(&Event::Progress {
+            done: exercises_done as usize,
+            total: exercises.len(),
+        });
+        return;
+    }
+    let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
+    println!(
+        "Progress: You completed {} / {} exercises ({:.1} %).",
+        exercises_done,
+        exercises.len(),
+        percentage_progress
+    );
+}
+
+// Reset the exercises, telling where the previous attempts were saved
+fn reset_exercises(exercises: &[&Exercise]) -> Result<(), ()> {
+    match pristine::reset(exercises) {
+        Ok(reset) => {
+            for exercise in reset.restored {
+                println!("Reset {exercise}");
+            }
+            for exercise in reset.missing {
+                warn!(
+                    "There is no original version of {} to reset it to, `rustlings init` keeps one before you change the exercises",
+                    exercise
                 );
-                println!("\n{FENISH_LINE}\n");
             }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+            if let Some(backup) = reset.backup_dir {
+                println!("Your previous attempts were saved to {}", backup.display());
             }
-        },
+            Ok(())
+        }
+        Err(e) => {
+            println!("Failed to reset the exercises: {e}");
+            Err(())
+        }
     }
 }
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
+// A command of the watch shell that the watch loop carries out
+enum WatchCommand {
+    Hint,
+    Run(String),
+    Reset,
+    List,
+    Skip,
+    Goto(String),
+    VerifyAll,
+    Quit,
+}
+
+fn spawn_watch_shell(commands: Sender<WatchCommand>) {
     println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
     thread::spawn(move || loop {
         let mut input = String::new();
         match io::stdin().read_line(&mut input) {
+            // There's nothing more to read once stdin is closed
+            Ok(0) => break,
             Ok(_) => {
                 let input = input.trim();
-                if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
+                let (command, name) = match input.split_once(char::is_whitespace) {
+                    Some((command, name)) => (command, name.trim()),
+                    None => (input, ""),
+                };
+                let command = match command {
+                    "hint" => WatchCommand::Hint,
+                    "run" | "goto" if name.is_empty() => {
+                        println!("usage: {command} <exercise name>");
+                        continue;
                     }
-                } else if input == "clear" {
-                    println!("\x1B[2J\x1B[1;1H");
-                } else if input.eq("quit") {
-                    should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
-                } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint   - prints the current exercise's hint");
-                    println!("  clear  - clears the screen");
-                    println!("  quit   - quits watch mode");
-                    println!("  !<cmd> - executes a command, like `!rustc --explain E0381`");
-                    println!("  help   - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
-                } else if let Some(cmd) = input.strip_prefix('!') {
-                    let parts: Vec<&str> = cmd.split_whitespace().collect();
-                    if parts.is_empty() {
-                        println!("no command provided");
-                    } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
-                        println!("failed to execute command `{}`: {}", cmd, e);
+                    "run" => WatchCommand::Run(name.to_string()),
+                    "goto" => WatchCommand::Goto(name.to_string()),
+                    "reset" => WatchCommand::Reset,
+                    "list" => WatchCommand::List,
+                    "skip" => WatchCommand::Skip,
+                    "verify-all" => WatchCommand::VerifyAll,
+                    "quit" => {
+                        println!("Bye!");
+                        WatchCommand::Quit
                     }
-                } else {
-                    println!("unknown command: {input}");
+                    "clear" => {
+                        println!("\x1B[2J\x1B[1;1H");
+                        continue;
+                    }
+                    "help" => {
+                        println!("Commands available to you in watch mode:");
+                        println!("  hint        - prints the current exercise's next hint");
+                        println!("  run <name>  - runs an exercise, like `rustlings run`");
+                        println!("  reset       - resets the current exercise, like `rustlings reset`");
+                        println!("  list        - lists the exercises, like `rustlings list`");
+                        println!("  skip        - leaves the current exercise for later and moves on");
+                        println!("  goto <name> - moves on to the given exercise");
+                        println!("  verify-all  - verifies all exercises, like `rustlings verify`");
+                        println!("  clear       - clears the screen");
+                        println!("  quit        - quits watch mode");
+                        println!("  !<cmd>      - executes a command, like `!rustc --explain E0381`");
+                        println!("  help        - displays this help message");
+                        println!();
+                        println!("Watch mode automatically re-evaluates the current exercise");
+                        println!("when you edit a file's contents.");
+                        continue;
+                    }
+                    _ => {
+                        if let Some(cmd) = input.strip_prefix('!') {
+                            let parts: Vec<&str> = cmd.split_whitespace().collect();
+                            if parts.is_empty() {
+                                println!("no command provided");
+                            } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
+                                println!("failed to execute command `{}`: {}", cmd, e);
+                            }
+                        } else {
+                            println!("unknown command: {input}");
+                        }
+                        continue;
+                    }
+                };
+                if commands.send(command).is_err() {
+                    break;
                 }
             }
             Err(error) => println!("error reading command: {error}"),
@@ -421,12 +744,45 @@ fn spawn_watch_shell(
     });
 }
 
+// Reveal the next hint of the exercise and print it, along with how to get
+// the one after if there are several. `again` tells the user how to ask for it.
+fn print_next_hint(exercise: &Exercise, again: &str) {
+    let levels = exercise.hint.levels();
+    let level = state::reveal_hint(exercise);
+    let hint = match level {
+        0 => "",
+        level => &levels[level - 1],
+    };
+    if message::is_json() {
+        message::emit(&Event::Hint {
+            exercise: &exercise.name,
+            hint,
+            level,
+            levels: levels.len(),
+        });
+    } else if levels.len() <= 1 {
+        println!("{hint}");
+    } else {
+        println!("Hint {level}/{}:", levels.len());
+        println!("{hint}");
+        if level < levels.len() {
+            println!();
+            println!("If you're still stuck, {again} for the next hint.");
+        }
+    }
+}
+
 fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
     if name.eq("next") {
-        exercises
-            .iter()
-            .find(|e| !e.looks_done())
+        ProgressState::load()
+            .next(exercises)
             .unwrap_or_else(|| {
+                if message::is_json() {
+                    message::emit(&Event::Error {
+                        message: "There are no more exercises to do next".to_string(),
+                    });
+                    std::process::exit(1)
+                }
                 println!("🎉 Congratulations! You have done all the exercises!");
                 println!("🔚 There are no more exercises to do next!");
                 std::process::exit(1)
@@ -436,6 +792,12 @@ fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
             .iter()
             .find(|e| e.name == name)
             .unwrap_or_else(|| {
+                if message::is_json() {
+                    message::emit(&Event::Error {
+                        message: format!("No exercise found for '{name}'"),
+                    });
+                    std::process::exit(1)
+                }
                 println!("No exercise found for '{name}'!");
                 std::process::exit(1)
             })
@@ -447,8 +809,70 @@ enum WatchStatus {
     Unfinished,
 }
 
+// Verify the exercises that aren't done in order, except for the
+// deferred ones, which are verified last in the order they were deferred
+fn verify_pending<'a>(
+    exercises: &'a [Exercise],
+    deferred: &[&'a Exercise],
+    verbose: bool,
+    success_hints: bool,
+) -> Result<(), &'a Exercise> {
+    let progress = ProgressState::load();
+    let is_deferred = |e: &Exercise| deferred.iter().any(|d| d.name == e.name);
+    let pending_exercises = exercises
+        .iter()
+        .filter(|e| !progress.is_done(e) && !is_deferred(e))
+        .chain(deferred.iter().copied().filter(|e| !progress.is_done(e)));
+    let num_done = exercises.iter().filter(|e| progress.is_done(e)).count();
+    verify(
+        pending_exercises,
+        (num_done, exercises.len()),
+        verbose,
+        success_hints,
+        false,
+    )
+}
+
+// Verify every exercise affected by a change, even after one of them failed,
+// and report how each of them did. Returns the first one that isn't done.
+fn recheck<'a>(
+    exercises: &[Exercise],
+    affected: &[&'a Exercise],
+    verbose: bool,
+    success_hints: bool,
+) -> Option<&'a Exercise> {
+    let progress = ProgressState::load();
+    let num_done = exercises.iter().filter(|e| progress.is_done(e)).count();
+    let results: Vec<(&Exercise, bool)> = affected
+        .iter()
+        .map(|&exercise| {
+            let progress = (num_done, exercises.len());
+            let done = verify([exercise], progress, verbose, success_hints, false).is_ok();
+            (exercise, done)
+        })
+        .collect();
+    if results.len() > 1 {
+        let progress = ProgressState::load();
+        println!("Rechecked the exercises affected by your changes:");
+        for (exercise, done) in &results {
+            if *done {
+                println!("  {} {exercise}", style("✓").green());
+            } else if progress.passed(exercise) {
+                println!("  {} {exercise} (not marked as done yet)", style("•").yellow());
+            } else {
+                println!("  {} {exercise}", style("✗").red());
+            }
+        }
+    }
+    results
+        .into_iter()
+        .find(|(_, done)| !done)
+        .map(|(exercise, _)| exercise)
+}
+
 fn watch(
     exercises: &[Exercise],
+    options: &WatchOptions,
     verbose: bool,
     success_hints: bool,
 ) -> notify::Result<WatchStatus> {
@@ -458,73 +882,93 @@ fn watch(
         println!("\x1Bc");
     }
 
-    let (tx, rx) = channel();
-    let should_quit = Arc::new(AtomicBool::new(false));
-
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
-    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
+    let (commands_tx, commands) = channel();
+    let watcher = ExerciseWatcher::new(options)?;
 
     clear_screen();
 
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(
-        exercises.iter(),
-        (0, exercises.len()),
-        verbose,
-        success_hints,
-    ) {
-        Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
-    };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
+    // Resume after the exercises that were already verified
+    let mut deferred: Vec<&Exercise> = Vec::new();
+    let mut current_exercise =
+        match verify_pending(exercises, &deferred, verbose, success_hints) {
+            Ok(_) => return Ok(WatchStatus::Finished),
+            Err(exercise) => exercise,
+        };
+    spawn_watch_shell(commands_tx);
     loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(
-                            pending_exercises,
-                            (num_done, exercises.len()),
-                            verbose,
-                            success_hints,
-                        ) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
-                        }
-                    }
+        // The exercises to check before moving on to the pending ones,
+        // if watch mode should move on
+        let mut next: Option<Vec<&Exercise>> = None;
+        match watcher.changes.recv_timeout(Duration::from_millis(200)) {
+            Ok(Ok(paths)) => {
+                let affected = DependencyMap::new(exercises).affected_exercises(&paths);
+                if !affected.is_empty() {
+                    // Editing a deferred exercise brings it back
+                    deferred.retain(|d| !affected.iter().any(|a| a.name == d.name));
+                    next = Some(affected);
                 }
-                _ => {}
-            },
+            }
+            Ok(Err(e)) => println!("watch error: {e:?}"),
             Err(RecvTimeoutError::Timeout) => {
-                // the timeout expired, just check the `should_quit` variable below then loop again
+                // the timeout expired, just check for commands below then loop again
             }
             Err(e) => println!("watch error: {e:?}"),
         }
-        // Check if we need to exit
-        if should_quit.load(Ordering::SeqCst) {
-            return Ok(WatchStatus::Unfinished);
+
+        while let Ok(command) = commands.try_recv() {
+            match command {
+                WatchCommand::Hint => print_next_hint(current_exercise, "type `hint` again"),
+                WatchCommand::Run(name) => match exercises.iter().find(|e| e.name == name) {
+                    Some(exercise) => {
+                        let _ = run(exercise, verbose);
+                    }
+                    None => println!("No exercise found for '{name}'!"),
+                },
+                // Watch mode verifies the exercise again once its file is restored
+                WatchCommand::Reset => {
+                    let _ = reset_exercises(&[current_exercise]);
+                }
+                WatchCommand::List => list_exercises(exercises, &ListArgs::default()),
+                WatchCommand::Skip => {
+                    deferred.retain(|d| d.name != current_exercise.name);
+                    deferred.push(current_exercise);
+                    next = Some(Vec::new());
+                }
+                WatchCommand::Goto(name) => match exercises.iter().find(|e| e.name == name) {
+                    Some(exercise) => {
+                        deferred.retain(|d| d.name != exercise.name);
+                        next = Some(vec![exercise]);
+                    }
+                    None => println!("No exercise found for '{name}'!"),
+                },
+                WatchCommand::VerifyAll => {
+                    clear_screen();
+                    match verify(exercises, (0, exercises.len()), verbose, success_hints, false) {
+                        Ok(_) => return Ok(WatchStatus::Finished),
+                        Err(exercise) => current_exercise = exercise,
+                    }
+                }
+                WatchCommand::Quit => return Ok(WatchStatus::Unfinished),
+            }
+        }
+
+        if let Some(affected) = next {
+            clear_screen();
+            let failed = recheck(exercises, &affected, verbose, success_hints);
+            match failed.map_or_else(
+                || verify_pending(exercises, &deferred, verbose, success_hints),
+                Err,
+            ) {
+                Ok(_) => return Ok(WatchStatus::Finished),
+                Err(exercise) => current_exercise = exercise,
+            }
         }
     }
 }
 
 fn rustc_exists() -> bool {
     Command::new("rustc")
-        .args(&["--version"])
+        .args(["--version"])
         .stdout(Stdio::null())
         .spawn()
         .and_then(|mut child| child.wait())
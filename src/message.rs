@@ -0,0 +1,164 @@
+use crate::diagnostics::Diagnostic;
+use crate::exercise::{Exercise, ExerciseOutput, Mode, Stage};
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// How rustlings reports what it is doing
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum MessageFormat {
+    // Colored text meant to be read by people
+    Human,
+    // Newline-delimited JSON events meant to be read by scripts and editors
+    Json,
+}
+
+impl FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            _ => Err(format!(
+                "unknown message format `{s}`, expected human or json"
+            )),
+        }
+    }
+}
+
+// The message format is chosen once at startup, like NO_EMOJI,
+// so it doesn't have to be passed down to every function that prints
+static JSON: AtomicBool = AtomicBool::new(false);
+
+pub fn set_format(format: MessageFormat) {
+    JSON.store(format == MessageFormat::Json, Ordering::Relaxed);
+}
+
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+// Something that happened while handling a command.
+// Each event is written as a JSON object with its kind in the `event` field.
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    // Checking an exercise started
+    Started {
+        exercise: &'a str,
+        path: &'a Path,
+        mode: Mode,
+    },
+    // The exercise compiled
+    Compiled {
+        exercise: &'a str,
+    },
+    // The exercise failed to compile, failed its tests or crashed
+    Failed {
+        exercise: &'a str,
+        stage: Stage,
+        timed_out: bool,
+        exit_code: Option<i32>,
+        stdout: String,
+        stderr: String,
+        diagnostics: Vec<&'a Diagnostic>,
+        // Whether each of the cases of the exercise passed, if it has any
+        cases: Vec<bool>,
+    },
+    // The exercise compiled and ran successfully.
+    // `done` is false while the exercise still has its `I AM NOT DONE` comment,
+    // `cached` is true if it was skipped because it passed before without changing since.
+    Passed {
+        exercise: &'a str,
+        done: bool,
+        cached: bool,
+        stdout: String,
+    },
+    // How many of the exercises are done
+    Progress {
+        done: usize,
+        total: usize,
+    },
+    // An exercise listed by `list`
+    Exercise {
+        name: &'a str,
+        path: &'a Path,
+        mode: Mode,
+        done: bool,
+        difficulty: Option<u8>,
+        tags: &'a [String],
+        requires: &'a [String],
+        estimated_minutes: Option<u32>,
+    },
+    // The hint of an exercise that was just revealed, the `level`th of `levels`
+    Hint {
+        exercise: &'a str,
+        hint: &'a str,
+        level: usize,
+        levels: usize,
+    },
+    // Something went wrong that isn't about a particular exercise
+    Error {
+        message: String,
+    },
+}
+
+impl<'a> Event<'a> {
+    pub fn started(exercise: &'a Exercise) -> Self {
+        Event::Started {
+            exercise: &exercise.name,
+            path: &exercise.path,
+            mode: exercise.mode,
+        }
+    }
+
+    pub fn failed(exercise: &'a Exercise, output: &'a ExerciseOutput) -> Self {
+        Event::Failed {
+            exercise: &exercise.name,
+            stage: output.stage,
+            timed_out: output.timed_out,
+            exit_code: output.exit_code,
+            stdout: console::strip_ansi_codes(&output.stdout).to_string(),
+            stderr: console::strip_ansi_codes(&output.stderr).to_string(),
+            diagnostics: output
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.is_relevant())
+                .collect(),
+            cases: output.cases.iter().map(|case| case.passed).collect(),
+        }
+    }
+}
+
+// Write the event to stdout as a single line of JSON
+pub fn emit(event: &Event) {
+    let mut line = serde_json::to_string(event).unwrap();
+    line.push('\n');
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    // Whoever reads the events may stop listening early, which is fine
+    handle
+        .write_all(line.as_bytes())
+        .and_then(|_| handle.flush())
+        .unwrap_or_else(|e| match e.kind() {
+            io::ErrorKind::BrokenPipe => std::process::exit(0),
+            _ => std::process::exit(1),
+        });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_event_format() {
+        let event = Event::Progress { done: 3, total: 5 };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"progress","done":3,"total":5}"#
+        );
+    }
+}
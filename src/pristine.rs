@@ -0,0 +1,140 @@
+use crate::exercise::{package_files, Exercise, Mode};
+use crate::history::Sources;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Where the original version of every exercise file is kept
+const PRISTINE_DIR: &str = ".rustlings/pristine";
+
+// Where the attempts of the user are kept when their exercises are reset
+const BACKUP_DIR: &str = ".rustlings/backup";
+
+// Keep a copy of the exercise files as they are now, as the original versions
+// that `reset` restores. There is no telling whether the user edited a file
+// already, so this only happens when `rustlings init` is run on purpose.
+// Exercises that have copies keep them unless `overwrite` is set, so that
+// running it again only picks up the exercises that were added since.
+// Returns how many exercises were copied.
+pub fn snapshot(exercises: &[Exercise], overwrite: bool) -> io::Result<usize> {
+    let pristine_dir = Path::new(PRISTINE_DIR);
+    let mut copied = 0;
+    for exercise in exercises {
+        if !overwrite && pristine_dir.join(&exercise.path).exists() {
+            continue;
+        }
+        for file in exercise.files() {
+            let copy = pristine_dir.join(&file);
+            if let Some(dir) = copy.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::copy(&file, &copy)?;
+        }
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+// The directory the original versions of the exercise files are kept in,
+// under the same paths as the exercise files
+pub fn dir() -> &'static Path {
+    Path::new(PRISTINE_DIR)
+}
+
+// The sources of the exercise as they were originally, which are all of its
+// files for an exercise that is a whole package
+pub fn original(exercise: &Exercise) -> io::Result<Sources> {
+    let files = original_files(exercise).ok_or(io::ErrorKind::NotFound)?;
+    files
+        .into_iter()
+        .map(|file| {
+            let source = fs::read_to_string(Path::new(PRISTINE_DIR).join(&file))?;
+            Ok((file, source))
+        })
+        .collect()
+}
+
+// The files of the exercise that have a pristine copy, or `None` if the
+// exercise has none, because it wasn't around when the copies were taken
+fn original_files(exercise: &Exercise) -> Option<Vec<PathBuf>> {
+    let pristine_dir = Path::new(PRISTINE_DIR);
+    let copy = pristine_dir.join(&exercise.path);
+    if !copy.exists() {
+        return None;
+    }
+    let files = match exercise.mode {
+        // Files of the package the user deleted are brought back too
+        Mode::Cargo => package_files(&copy)
+            .into_iter()
+            .filter_map(|file| Some(file.strip_prefix(pristine_dir).ok()?.to_path_buf()))
+            .collect(),
+        _ => exercise
+            .files()
+            .into_iter()
+            .filter(|file| pristine_dir.join(file).is_file())
+            .collect(),
+    };
+    Some(files)
+}
+
+// How resetting exercises went
+pub struct Reset<'a> {
+    // The exercises that were restored
+    pub restored: Vec<&'a Exercise>,
+    // The exercises that couldn't be, having no pristine copy
+    pub missing: Vec<&'a Exercise>,
+    // The directory the backups were saved to, if anything was backed up
+    pub backup_dir: Option<PathBuf>,
+}
+
+// Restore the exercises from their pristine copies, backing up the files that changed.
+// Exercises without pristine copies are left as they are.
+pub fn reset<'a>(exercises: &[&'a Exercise]) -> io::Result<Reset<'a>> {
+    let pristine_dir = Path::new(PRISTINE_DIR);
+    let mut restored = Vec::new();
+    let mut missing = Vec::new();
+    let mut restores = Vec::new();
+    for &exercise in exercises {
+        let Some(files) = original_files(exercise) else {
+            missing.push(exercise);
+            continue;
+        };
+        for file in files {
+            let pristine = fs::read(pristine_dir.join(&file))?;
+            restores.push((file, pristine));
+        }
+        restored.push(exercise);
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default();
+    let backup_dir = Path::new(BACKUP_DIR).join(timestamp.to_string());
+    let mut backed_up = false;
+    for (file, pristine) in restores {
+        match fs::read(&file) {
+            Ok(attempt) if attempt == pristine => continue,
+            Ok(attempt) => {
+                let backup = backup_dir.join(&file);
+                if let Some(dir) = backup.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(backup, attempt)?;
+                backed_up = true;
+            }
+            Err(_) => {
+                if let Some(dir) = file.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+            }
+        }
+        fs::write(&file, pristine)?;
+    }
+    Ok(Reset {
+        restored,
+        missing,
+        backup_dir: backed_up.then_some(backup_dir),
+    })
+}
@@ -0,0 +1,220 @@
+use crate::cicv::{ExerciseCheckList, ExerciseResult};
+use crate::exercise::Stage;
+use crate::libtest;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+// The formats the results of `cicvverify` can be written in
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ReportFormat {
+    // The check_result.json read by the classroom workflow
+    Json,
+    // A JUnit XML test suite, understood by most CI systems
+    Junit,
+    // The Test Anything Protocol
+    Tap,
+    // A summary table, e.g. for GitHub's job summaries
+    Markdown,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ReportFormat::Json),
+            "junit" => Ok(ReportFormat::Junit),
+            "tap" => Ok(ReportFormat::Tap),
+            "markdown" => Ok(ReportFormat::Markdown),
+            _ => Err(format!(
+                "unknown format `{s}`, expected one of json, junit, tap or markdown"
+            )),
+        }
+    }
+}
+
+// Write the report to the given path, creating its directory if needed
+pub fn write_report(
+    check_list: &ExerciseCheckList,
+    path: &Path,
+    format: ReportFormat,
+) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, render(check_list, format))
+}
+
+pub fn render(check_list: &ExerciseCheckList, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Json => serde_json::to_string_pretty(check_list).unwrap(),
+        ReportFormat::Junit => render_junit(check_list),
+        ReportFormat::Tap => render_tap(check_list),
+        ReportFormat::Markdown => render_markdown(check_list),
+    }
+}
+
+// A short description of why the exercise failed, with the tests that
+// failed, like "failed at the test stage, 4/6 tests passing (a, b)"
+fn failure_message(result: &ExerciseResult) -> String {
+    if result.timed_out {
+        return "timed out".to_string();
+    }
+    let message = match result.stage {
+        Some(stage) => format!("failed at the {} stage", stage),
+        None => "failed".to_string(),
+    };
+    let tests = match (result.stage, &result.grader) {
+        (Some(Stage::Grader), Some(grader)) => &grader.tests,
+        _ => &result.tests,
+    };
+    let failed = libtest::failed(tests);
+    if failed.is_empty() {
+        return message;
+    }
+    format!(
+        "{message}, {} ({})",
+        libtest::summary(tests),
+        failed.join(", ")
+    )
+}
+
+fn render_junit(check_list: &ExerciseCheckList) -> String {
+    let statistics = &check_list.statistics;
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuite name=\"rustlings\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">",
+        statistics.total_exercations,
+        statistics.total_failures,
+        statistics.total_time_ms as f64 / 1000.0
+    );
+    for result in &check_list.exercises {
+        let _ = write!(
+            out,
+            "  <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            escape_xml(&result.name),
+            escape_xml(&result.category),
+            result.time_ms as f64 / 1000.0
+        );
+        if result.result {
+            out.push_str("/>\n");
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            ">\n    <failure message=\"{}\">{}</failure>",
+            escape_xml(&failure_message(result)),
+            escape_xml(&result.stderr)
+        );
+        if !result.stdout.is_empty() {
+            let _ = writeln!(
+                out,
+                "    <system-out>{}</system-out>",
+                escape_xml(&result.stdout)
+            );
+        }
+        out.push_str("  </testcase>\n");
+    }
+    out.push_str("</testsuite>\n");
+    out
+}
+
+fn escape_xml(s: &str) -> String {
+    s.chars()
+        .filter(|&c| c == '\n' || c == '\t' || !c.is_control())
+        .fold(String::with_capacity(s.len()), |mut out, c| {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                '\'' => out.push_str("&apos;"),
+                c => out.push(c),
+            }
+            out
+        })
+}
+
+fn render_tap(check_list: &ExerciseCheckList) -> String {
+    let mut out = String::from("TAP version 13\n");
+    let _ = writeln!(out, "1..{}", check_list.exercises.len());
+    for (i, result) in check_list.exercises.iter().enumerate() {
+        if result.result {
+            let _ = writeln!(out, "ok {} - {}", i + 1, result.name);
+            continue;
+        }
+        let _ = writeln!(out, "not ok {} - {}", i + 1, result.name);
+        out.push_str("  ---\n");
+        let _ = writeln!(out, "  message: {:?}", failure_message(result));
+        let _ = writeln!(out, "  duration_ms: {}", result.time_ms);
+        if !result.stderr.is_empty() {
+            out.push_str("  stderr: |\n");
+            for line in result.stderr.lines() {
+                let _ = writeln!(out, "    {line}");
+            }
+        }
+        out.push_str("  ...\n");
+    }
+    let statistics = &check_list.statistics;
+    let _ = writeln!(
+        out,
+        "# score: {} / {} points ({:.1} %)",
+        statistics.earned_points, statistics.total_points, statistics.score
+    );
+    out
+}
+
+fn render_markdown(check_list: &ExerciseCheckList) -> String {
+    let statistics = &check_list.statistics;
+    let mut out = String::from("# Rustlings results\n\n");
+    if let Some(user_name) = &check_list.user_name {
+        let _ = writeln!(out, "User: {user_name}\n");
+    }
+    let _ = writeln!(
+        out,
+        "Passed {} / {} exercises in {:.1} s, scoring {} / {} points ({:.1} %).\n",
+        statistics.total_succeeds,
+        statistics.total_exercations,
+        statistics.total_time_ms as f64 / 1000.0,
+        statistics.earned_points,
+        statistics.total_points,
+        statistics.score
+    );
+    out.push_str("| Category | Exercises | Points |\n");
+    out.push_str("| --- | --- | --- |\n");
+    for (name, category) in &statistics.categories {
+        let _ = writeln!(
+            out,
+            "| {} | {} / {} | {} / {} |",
+            name,
+            category.total_succeeds,
+            category.total_exercations,
+            category.earned_points,
+            category.total_points
+        );
+    }
+    out.push('\n');
+    out.push_str("| Exercise | Result | Points | Hints | Time |\n");
+    out.push_str("| --- | --- | --- | --- | --- |\n");
+    for result in &check_list.exercises {
+        let mut status = if result.result {
+            "✅ passed".to_string()
+        } else {
+            format!("❌ {}", failure_message(result))
+        };
+        if result.tests_modified {
+            status.push_str(", ⚠️ tests modified");
+        }
+        let earned = if result.result { result.points } else { 0 };
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} / {} | {} | {} ms |",
+            result.name, status, earned, result.points, result.hints_used, result.time_ms
+        );
+    }
+    out
+}
@@ -1,7 +1,10 @@
-use std::process::Command;
-
-use crate::exercise::{Exercise, Mode};
-use crate::verify::test;
+use crate::exercise::{Exercise, Mode, Stage};
+use crate::message;
+use crate::state;
+use crate::verify::{
+    check_with_events, print_compile_errors, test, warn_failed_cases, warn_timed_out,
+    warn_unexpected_output,
+};
 use indicatif::ProgressBar;
 
 // Invoke the rust compiler on the path of the given exercise,
@@ -9,26 +12,19 @@ use indicatif::ProgressBar;
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
 pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
-    match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
-        Mode::Compile => compile_and_run(exercise)?,
-        Mode::Clippy => compile_and_run(exercise)?,
-        Mode::BuildScript => test(exercise, verbose)?,
-    }
-    Ok(())
-}
-
-// Resets the exercise by stashing the changes.
-pub fn reset(exercise: &Exercise) -> Result<(), ()> {
-    let command = Command::new("git")
-        .args(["stash", "--"])
-        .arg(&exercise.path)
-        .spawn();
-
-    match command {
-        Ok(_) => Ok(()),
-        Err(_) => Err(()),
-    }
+    let result = if message::is_json() {
+        check_with_events(exercise, true).map(|_| ())
+    } else {
+        match exercise.mode {
+            Mode::Test => test(exercise, verbose),
+            Mode::Compile => compile_and_run(exercise),
+            Mode::Clippy => compile_and_run(exercise),
+            Mode::BuildScript => test(exercise, verbose),
+            Mode::Cargo => test(exercise, verbose),
+        }
+    };
+    state::record(exercise, result.is_ok());
+    result
 }
 
 // Invoke the rust compiler on the path of the given exercise
@@ -48,7 +44,7 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
                 "Compilation of {} failed!, Compiler error message:\n",
                 exercise
             );
-            println!("{}", output.stderr);
+            print_compile_errors(&output);
             return Err(());
         }
     };
@@ -59,15 +55,31 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
 
     match result {
         Ok(output) => {
-            println!("{}", output.stdout);
+            if output.cases.is_empty() {
+                println!("{}", output.stdout);
+            } else {
+                println!("All {} cases passed.", output.cases.len());
+            }
             success!("Successfully ran {}", exercise);
             Ok(())
         }
+        Err(output) if !output.cases.is_empty() => {
+            warn_failed_cases(exercise, &output);
+            Err(())
+        }
+        Err(output) if output.stage == Stage::Output => {
+            warn_unexpected_output(exercise, &output);
+            Err(())
+        }
         Err(output) => {
             println!("{}", output.stdout);
             println!("{}", output.stderr);
 
-            warn!("Ran {} with errors", exercise);
+            if output.timed_out {
+                warn_timed_out(exercise);
+            } else {
+                warn!("Ran {} with errors", exercise);
+            }
             Err(())
         }
     }
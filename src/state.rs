@@ -0,0 +1,250 @@
+use crate::exercise::{Exercise, Mode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Where the verified progress is kept. It lives in the ignored .rustlings
+// directory so that it doesn't end up in the commits of the user.
+pub const STATE_FILE: &str = ".rustlings/state.json";
+
+// The outcome of the last time an exercise was verified
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ExerciseRecord {
+    // Whether the exercise compiled and passed its checks
+    pub passed: bool,
+    // When the exercise was verified, in seconds since the Unix epoch
+    pub timestamp: u64,
+    // The hash of the source file that was verified
+    pub source_hash: String,
+}
+
+// The progress of the user as verified by rustlings, keyed by exercise name
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct ProgressState {
+    #[serde(default)]
+    pub exercises: BTreeMap<String, ExerciseRecord>,
+    // How many levels of their hints have been revealed
+    #[serde(default)]
+    pub hints: BTreeMap<String, usize>,
+}
+
+impl ProgressState {
+    // Load the state, starting over if there is none or it can't be read
+    pub fn load() -> ProgressState {
+        Self::load_from(Path::new(STATE_FILE))
+    }
+
+    fn load_from(path: &Path) -> ProgressState {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_to(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self).unwrap())
+    }
+
+    // Remember the result of verifying the exercise as it is now
+    pub fn record(&mut self, exercise: &Exercise, passed: bool) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let record = ExerciseRecord {
+            passed,
+            timestamp,
+            source_hash: source_hash(exercise).unwrap_or_default(),
+        };
+        self.exercises.insert(exercise.name.clone(), record);
+    }
+
+    // How many hints of the exercise have been revealed so far
+    pub fn hints_used(&self, exercise: &Exercise) -> usize {
+        self.hints.get(&exercise.name).copied().unwrap_or_default()
+    }
+
+    // Reveal the next hint of the exercise, if there is one left.
+    // Returns how many hints are revealed now.
+    pub fn reveal_hint(&mut self, exercise: &Exercise) -> usize {
+        let levels = exercise.hint.levels().len();
+        let used = self.hints.entry(exercise.name.clone()).or_default();
+        *used = (*used + 1).min(levels);
+        *used
+    }
+
+    // Whether the exercise is done: it passed the last time it was verified,
+    // has no `I AM NOT DONE` comment left and hasn't been edited since.
+    // Any edit makes it stale until it's verified again, and an exercise that
+    // was never verified isn't done, whatever its comment says.
+    pub fn is_done(&self, exercise: &Exercise) -> bool {
+        self.exercises.get(&exercise.name).is_some_and(|record| {
+            record.passed
+                && source_hash(exercise).as_ref() == Some(&record.source_hash)
+                && exercise.looks_done()
+        })
+    }
+
+    // Whether the exercise passed the last time it was verified
+    pub fn passed(&self, exercise: &Exercise) -> bool {
+        self.exercises
+            .get(&exercise.name)
+            .is_some_and(|record| record.passed)
+    }
+
+    // The exercise to do next: the first pending one whose prerequisites are all done,
+    // or simply the first pending one if none of them has its prerequisites done
+    pub fn next<'a>(&self, exercises: &'a [Exercise]) -> Option<&'a Exercise> {
+        let is_done = |name: &String| {
+            exercises
+                .iter()
+                .find(|e| &e.name == name)
+                .is_none_or(|e| self.is_done(e))
+        };
+        let mut pending = exercises.iter().filter(|e| !self.is_done(e));
+        pending
+            .clone()
+            .find(|e| e.requires.iter().all(is_done))
+            .or_else(|| pending.next())
+    }
+}
+
+// Record the result of verifying the exercise in the state file.
+// Failing to save it only means that the exercise counts as
+// unverified next time, so errors are ignored.
+pub fn record(exercise: &Exercise, passed: bool) {
+    let path = Path::new(STATE_FILE);
+    let mut state = ProgressState::load_from(path);
+    state.record(exercise, passed);
+    let _ = state.save_to(path);
+}
+
+// Reveal the next hint of the exercise and remember it in the state file.
+// Returns how many hints are revealed now.
+pub fn reveal_hint(exercise: &Exercise) -> usize {
+    let path = Path::new(STATE_FILE);
+    let mut state = ProgressState::load_from(path);
+    let revealed = state.reveal_hint(exercise);
+    let _ = state.save_to(path);
+    revealed
+}
+
+// The hash of the source of the exercise, which is the contents of its
+// file, or the names and contents of every file of a Cargo exercise
+fn source_hash(exercise: &Exercise) -> Option<String> {
+    let source = match exercise.mode {
+        Mode::Cargo => {
+            let mut source = Vec::new();
+            for file in exercise.files() {
+                source.extend(file.to_string_lossy().bytes());
+                source.push(0);
+                source.extend(fs::read(&file).ok()?);
+                source.push(0);
+            }
+            source
+        }
+        _ => fs::read(&exercise.path).ok()?,
+    };
+    Some(format!("{:016x}", fnv1a(&source)))
+}
+
+// The 64-bit FNV-1a hash of the bytes. Unlike the hasher of the standard library,
+// it is guaranteed to stay the same across Rust versions.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::{Hint, Mode};
+    use std::env;
+    use std::path::PathBuf;
+
+    fn exercise(path: &Path) -> Exercise {
+        Exercise {
+            name: "exercise".into(),
+            path: path.to_path_buf(),
+            mode: Mode::Compile,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_verified_state() {
+        let exercise = exercise(Path::new("tests/fixture/state/finished_exercise.rs"));
+        let mut state = ProgressState::default();
+        assert!(!state.is_done(&exercise));
+
+        state.record(&exercise, false);
+        assert!(!state.is_done(&exercise));
+
+        state.record(&exercise, true);
+        assert!(state.is_done(&exercise));
+    }
+
+    #[test]
+    fn test_next_exercise_has_its_prerequisites_done() {
+        let mut first = exercise(Path::new("tests/fixture/state/pending_exercise.rs"));
+        first.name = "first".into();
+        let mut second = exercise(Path::new("tests/fixture/state/pending_test_exercise.rs"));
+        second.name = "second".into();
+        let mut done = exercise(Path::new("tests/fixture/state/finished_exercise.rs"));
+        done.name = "done".into();
+        let mut state = ProgressState::default();
+        state.record(&done, true);
+
+        first.requires = vec!["done".into()];
+        let exercises = [done.clone(), first.clone(), second.clone()];
+        assert_eq!(state.next(&exercises).unwrap().name, "first");
+
+        first.requires = vec!["second".into()];
+        let exercises = [done.clone(), first.clone(), second.clone()];
+        assert_eq!(state.next(&exercises).unwrap().name, "second");
+
+        second.requires = vec!["first".into()];
+        let exercises = [done, first, second];
+        assert_eq!(state.next(&exercises).unwrap().name, "first");
+    }
+
+    #[test]
+    fn test_reveal_hints() {
+        let mut exercise = exercise(Path::new("exercise.rs"));
+        exercise.hint = Hint::Levels(vec!["vague".into(), "specific".into()]);
+        let mut state = ProgressState::default();
+        assert_eq!(state.hints_used(&exercise), 0);
+        assert_eq!(state.reveal_hint(&exercise), 1);
+        assert_eq!(state.reveal_hint(&exercise), 2);
+        assert_eq!(state.reveal_hint(&exercise), 2);
+        assert_eq!(state.hints_used(&exercise), 2);
+    }
+
+    #[test]
+    fn test_edited_exercise_is_stale() {
+        let dir = env::temp_dir().join(format!("rustlings_state_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path: PathBuf = dir.join("exercise.rs");
+        fs::write(&path, "fn main() {}\n").unwrap();
+        let exercise = exercise(&path);
+
+        let state_path = dir.join(STATE_FILE);
+        let mut state = ProgressState::default();
+        state.record(&exercise, true);
+        state.save_to(&state_path).unwrap();
+        let state = ProgressState::load_from(&state_path);
+        assert!(state.is_done(&exercise));
+
+        fs::write(&path, "fn main() { println!(\"edited\"); }\n").unwrap();
+        assert!(!state.is_done(&exercise));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
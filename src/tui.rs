@@ -0,0 +1,487 @@
+use crate::deps::DependencyMap;
+use crate::exercise::{Exercise, ExerciseOutput, Stage, State};
+use crate::pristine;
+use crate::state::{self, ProgressState};
+use crate::verify::check_quietly;
+use crate::watcher::{ExerciseWatcher, WatchOptions};
+use crate::WatchStatus;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::time::Duration;
+
+// How long to wait for a key press before looking for file changes again
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// The status of an exercise in the list
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Status {
+    // Not verified since rustlings started, and not done before either
+    Pending,
+    // Failed to compile or to pass its checks
+    Failed,
+    // Passed its checks, but still has its `I AM NOT DONE` comment
+    Passed,
+    // Passed its checks and has no `I AM NOT DONE` comment left
+    Done,
+}
+
+impl Status {
+    fn symbol(self) -> &'static str {
+        match self {
+            Status::Pending => "·",
+            Status::Failed => "✗",
+            Status::Passed => "•",
+            Status::Done => "✓",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Status::Pending => Color::Gray,
+            Status::Failed => Color::Red,
+            Status::Passed => Color::Yellow,
+            Status::Done => Color::Green,
+        }
+    }
+}
+
+// Everything the watch UI shows
+struct App<'a> {
+    exercises: &'a [Exercise],
+    statuses: Vec<Status>,
+    // The progress as of the start, along with the hints revealed since
+    progress: ProgressState,
+    // The exercise the output and the hint are about
+    selected: ListState,
+    // The output of the last check of every exercise
+    outputs: Vec<Text>,
+    scroll: u16,
+    // Whether the hint pane is open
+    show_hint: bool,
+    // A short message for the status line, like the outcome of a reset
+    message: String,
+    // Whether the next `r` resets the selected exercise
+    confirm_reset: bool,
+}
+
+// The lines of the output pane, without the color codes of the compiler
+type Text = Vec<Line<'static>>;
+
+impl<'a> App<'a> {
+    fn new(exercises: &'a [Exercise]) -> Self {
+        let progress = ProgressState::load();
+        let statuses = exercises
+            .iter()
+            .map(|e| match progress.is_done(e) {
+                true => Status::Done,
+                false => Status::Pending,
+            })
+            .collect();
+        App {
+            exercises,
+            statuses,
+            progress,
+            selected: ListState::default().with_selected(Some(0)),
+            outputs: vec![Vec::new(); exercises.len()],
+            scroll: 0,
+            show_hint: false,
+            message: String::new(),
+            confirm_reset: false,
+        }
+    }
+
+    fn current(&self) -> usize {
+        self.selected.selected().unwrap_or_default()
+    }
+
+    fn num_done(&self) -> usize {
+        self.statuses.iter().filter(|s| **s == Status::Done).count()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.num_done() == self.exercises.len()
+    }
+
+    fn select(&mut self, index: usize) {
+        if index != self.current() {
+            self.show_hint = false;
+        }
+        self.selected.select(Some(index));
+        self.scroll = 0;
+    }
+
+    // Check the exercise and show what came out of it.
+    // Returns whether the exercise is done.
+    fn check(&mut self, index: usize) -> bool {
+        let exercise = &self.exercises[index];
+        let (status, output) = match check_quietly(exercise) {
+            Ok(output) => match exercise.state() {
+                State::Done => (Status::Done, success_text(exercise, &output)),
+                State::Pending(context) => {
+                    let mut text = success_text(exercise, &output);
+                    text.push(Line::raw(""));
+                    text.push(Line::raw(
+                        "You can keep working on this exercise, or jump into the next one by removing the `I AM NOT DONE` comment:",
+                    ));
+                    text.push(Line::raw(""));
+                    text.extend(context.into_iter().map(|context_line| {
+                        let line = Line::raw(format!(
+                            "{:>3} |  {}",
+                            context_line.number, context_line.line
+                        ));
+                        match context_line.important {
+                            true => line.style(Style::default().add_modifier(Modifier::BOLD)),
+                            false => line,
+                        }
+                    }));
+                    (Status::Passed, text)
+                }
+            },
+            Err(output) => (Status::Failed, failure_text(exercise, &output)),
+        };
+        self.statuses[index] = status;
+        self.outputs[index] = output;
+        self.select(index);
+        status == Status::Done
+    }
+
+    // Check a single exercise, letting the user know it's being checked
+    fn check_one(&mut self, terminal: &mut DefaultTerminal, index: usize) -> io::Result<()> {
+        self.message = format!("Checking {}...", self.exercises[index]);
+        terminal.draw(|frame| self.draw(frame))?;
+        self.check(index);
+        self.message.clear();
+        Ok(())
+    }
+
+    // Check every exercise affected by a change, even after one of them failed,
+    // then the exercises that aren't done in order, stopping at the first one
+    // that isn't done, like `verify`
+    fn check_pending(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        affected: &[usize],
+    ) -> io::Result<()> {
+        let mut first_failed = None;
+        for &index in affected {
+            self.message = format!("Checking {}...", self.exercises[index]);
+            terminal.draw(|frame| self.draw(frame))?;
+            if !self.check(index) && first_failed.is_none() {
+                first_failed = Some(index);
+            }
+        }
+        let rechecked = match affected.len() {
+            0 | 1 => String::new(),
+            _ => affected
+                .iter()
+                .map(|&i| format!("{} {}", self.statuses[i].symbol(), self.exercises[i].name))
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        if let Some(index) = first_failed {
+            self.select(index);
+        } else {
+            let pending: Vec<usize> = (0..self.exercises.len())
+                .filter(|&i| self.statuses[i] != Status::Done)
+                .collect();
+            for index in pending {
+                self.message = format!("Checking {}...", self.exercises[index]);
+                terminal.draw(|frame| self.draw(frame))?;
+                if !self.check(index) {
+                    break;
+                }
+            }
+        }
+        self.message = if self.is_finished() {
+            "All exercises completed! Press q to quit.".to_string()
+        } else if !rechecked.is_empty() {
+            format!("Rechecked {rechecked}")
+        } else {
+            String::new()
+        };
+        Ok(())
+    }
+
+    // Reset the selected exercise, which the watcher then checks again
+    fn reset(&mut self) {
+        let exercise = &self.exercises[self.current()];
+        self.message = match pristine::reset(&[exercise]) {
+            Ok(reset) if !reset.missing.is_empty() => {
+                format!("There is no original version of {exercise}, `rustlings init` keeps one")
+            }
+            Ok(reset) => match reset.backup_dir {
+                Some(backup_dir) => format!(
+                    "Reset {exercise}, your previous attempt was saved to {}",
+                    backup_dir.display()
+                ),
+                None => format!("Reset {exercise}"),
+            },
+            Err(e) => format!("Failed to reset {exercise}: {e}"),
+        };
+    }
+
+    // Open the hint pane, revealing the next hint, or close it
+    fn toggle_hint(&mut self) {
+        self.show_hint = !self.show_hint;
+        if self.show_hint {
+            let exercise = &self.exercises[self.current()];
+            state::reveal_hint(exercise);
+            self.progress.reveal_hint(exercise);
+        }
+    }
+
+    fn hint_text(&self) -> Text {
+        let exercise = &self.exercises[self.current()];
+        let levels = exercise.hint.levels();
+        let used = self.progress.hints_used(exercise);
+        let mut text = Vec::new();
+        for (level, hint) in levels.iter().enumerate().take(used) {
+            if levels.len() > 1 {
+                text.push(Line::styled(
+                    format!("Hint {}/{}:", level + 1, levels.len()),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ));
+            }
+            text.extend(hint.lines().map(|line| Line::raw(line.to_string())));
+            text.push(Line::raw(""));
+        }
+        if used < levels.len() {
+            text.push(Line::raw(
+                "If you're still stuck, press h twice for the next hint.",
+            ));
+        }
+        text
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, progress, help] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list, right] =
+            Layout::horizontal([Constraint::Length(32), Constraint::Min(0)]).areas(main);
+
+        let items: Vec<ListItem> = self
+            .exercises
+            .iter()
+            .zip(&self.statuses)
+            .map(|(exercise, status)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(status.symbol(), Style::default().fg(status.color())),
+                    Span::raw(format!(" {}", exercise.name)),
+                ]))
+            })
+            .collect();
+        let items = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Exercises"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(items, list, &mut self.selected);
+
+        let exercise = &self.exercises[self.current()];
+        let output_area = if self.show_hint {
+            let hint = self.hint_text();
+            let height = (hint.len() as u16 + 2).min(right.height / 2);
+            let [output, hint_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(height)]).areas(right);
+            frame.render_widget(
+                Paragraph::new(hint)
+                    .wrap(Wrap { trim: false })
+                    .block(Block::default().borders(Borders::ALL).title("Hint")),
+                hint_area,
+            );
+            output
+        } else {
+            right
+        };
+        frame.render_widget(
+            Paragraph::new(self.outputs[self.current()].clone())
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(exercise.to_string()),
+                ),
+            output_area,
+        );
+
+        let (done, total) = (self.num_done(), self.exercises.len());
+        frame.render_widget(
+            Gauge::default()
+                .block(Block::default().borders(Borders::ALL).title("Progress"))
+                .gauge_style(Style::default().fg(Color::Green))
+                .ratio(done as f64 / total.max(1) as f64)
+                .label(format!("{done}/{total}")),
+            progress,
+        );
+
+        let keys = "n/p: next/previous  ↑/↓: scroll  h: hint  r: reset  q: quit";
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(keys, Style::default().fg(Color::DarkGray)),
+                Span::raw("  "),
+                Span::raw(self.message.as_str()),
+            ])),
+            help,
+        );
+    }
+}
+
+// What to show once the exercise passed its checks
+fn success_text(exercise: &Exercise, output: &ExerciseOutput) -> Text {
+    let mut text = vec![Line::styled(
+        format!("✓ Successfully checked {exercise}!"),
+        Style::default().fg(Color::Green),
+    )];
+    let stdout = console::strip_ansi_codes(&output.stdout);
+    if !output.cases.is_empty() {
+        text.push(Line::raw(""));
+        text.push(Line::raw(format!(
+            "All {} cases passed.",
+            output.cases.len()
+        )));
+    } else if !stdout.trim().is_empty() {
+        text.push(Line::raw(""));
+        text.push(Line::raw("Output:"));
+        text.extend(stdout.lines().map(|line| Line::raw(line.to_string())));
+    }
+    text
+}
+
+// What to show when the exercise failed, as `verify` would print it
+fn failure_text(exercise: &Exercise, output: &ExerciseOutput) -> Text {
+    let heading = match output.timed_out {
+        true => format!("⚠️  {exercise} timed out after {:?}", exercise.timeout()),
+        false => format!(
+            "⚠️  {} of {exercise} failed! Please try again.",
+            output.stage
+        ),
+    };
+    let mut text = vec![
+        Line::styled(heading, Style::default().fg(Color::Red)),
+        Line::raw(""),
+    ];
+    for (number, case) in output.cases.iter().enumerate() {
+        let (mark, color) = if case.passed {
+            ("✓", Color::Green)
+        } else {
+            ("✗", Color::Red)
+        };
+        text.push(Line::styled(
+            format!("{mark} Case {}", number + 1),
+            Style::default().fg(color),
+        ));
+    }
+    // The output of exercises with cases is the one of the first case that failed
+    let failed_case = output.cases.iter().position(|case| !case.passed);
+    if let Some(case) = failed_case.and_then(|index| exercise.cases.get(index)) {
+        text.push(Line::raw(""));
+        text.push(Line::raw("Input:"));
+        text.extend(case.input.lines().map(|line| Line::raw(line.to_string())));
+        text.push(Line::raw(""));
+    }
+    for stream in [&output.stdout, &output.stderr] {
+        let stream = console::strip_ansi_codes(stream);
+        text.extend(stream.lines().map(|line| Line::raw(line.to_string())));
+    }
+    let expected = match failed_case {
+        Some(index) => exercise.cases.get(index).map(|case| &case.expected_output),
+        None => exercise.expected_output.as_ref(),
+    };
+    if output.stage == Stage::Output {
+        if let Some(Ok(expected)) = expected.map(|e| e.text()) {
+            text.push(Line::raw(""));
+            text.push(Line::raw("Expected output:"));
+            text.extend(expected.lines().map(|line| Line::raw(line.to_string())));
+        }
+    }
+    text
+}
+
+// Puts the terminal back the way it was when watch mode ends, even on errors.
+// `ratatui::init` takes care of panics.
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+// Watch mode in a full screen UI. It checks the exercises like `watch`
+// does whenever a file changes, and lets the user move between them.
+pub fn watch(exercises: &[Exercise], options: &WatchOptions) -> notify::Result<WatchStatus> {
+    // There would be nothing to select
+    if exercises.is_empty() {
+        return Ok(WatchStatus::Finished);
+    }
+    let watcher = ExerciseWatcher::new(options)?;
+
+    let mut terminal = ratatui::init();
+    let _guard = TerminalGuard;
+    let mut app = App::new(exercises);
+    app.check_pending(&mut terminal, &[])?;
+    run(&mut app, &mut terminal, &watcher)?;
+    Ok(match app.is_finished() {
+        true => WatchStatus::Finished,
+        false => WatchStatus::Unfinished,
+    })
+}
+
+// The event loop, until the user quits
+fn run(app: &mut App, terminal: &mut DefaultTerminal, watcher: &ExerciseWatcher) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        while let Ok(changes) = watcher.changes.try_recv() {
+            match changes {
+                Ok(paths) => {
+                    let affected = DependencyMap::new(app.exercises).affected(&paths);
+                    if !affected.is_empty() {
+                        app.check_pending(terminal, &affected)?;
+                    }
+                }
+                Err(e) => app.message = format!("watch error: {e}"),
+            }
+        }
+
+        if !event::poll(POLL_INTERVAL)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let confirm_reset = std::mem::take(&mut app.confirm_reset);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char('n') | KeyCode::Right if app.current() + 1 < app.exercises.len() => {
+                app.check_one(terminal, app.current() + 1)?;
+            }
+            KeyCode::Char('p') | KeyCode::Left if app.current() > 0 => {
+                app.check_one(terminal, app.current() - 1)?;
+            }
+            KeyCode::Up | KeyCode::Char('k') => app.scroll = app.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => app.scroll = app.scroll.saturating_add(1),
+            KeyCode::PageUp => app.scroll = app.scroll.saturating_sub(10),
+            KeyCode::PageDown => app.scroll = app.scroll.saturating_add(10),
+            KeyCode::Char('h') => app.toggle_hint(),
+            KeyCode::Char('r') if confirm_reset => app.reset(),
+            KeyCode::Char('r') => {
+                app.message = format!("Press r again to reset {}", app.exercises[app.current()]);
+                app.confirm_reset = true;
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,532 @@
+use crate::exercise::{Exercise, Hint, Mode, OutputMatch};
+use glob::{glob_with, MatchOptions};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use std::path::{Component, Path, PathBuf};
+
+const MODES: [&str; 5] = ["compile", "test", "clippy", "buildscript", "cargo"];
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Severity {
+    // The exercises can't be loaded or won't work
+    Error,
+    // Something looks off, but rustlings can work with it
+    Warning,
+}
+
+// A problem found in info.toml, with the line of the entry it's about if there is one
+#[derive(PartialEq, Debug)]
+pub struct Problem {
+    pub severity: Severity,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Problem {
+    fn error(line: Option<usize>, message: String) -> Self {
+        Problem {
+            severity: Severity::Error,
+            line,
+            message,
+        }
+    }
+
+    fn warning(line: Option<usize>, message: String) -> Self {
+        Problem {
+            severity: Severity::Warning,
+            line,
+            message,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+// Formats the problem like "info.toml:12: error: duplicate name `intro1`"
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match self.line {
+            Some(line) => write!(f, "info.toml:{line}: {severity}: {}", self.message),
+            None => write!(f, "info.toml: {severity}: {}", self.message),
+        }
+    }
+}
+
+// Where the entries of info.toml start, to point problems to the right lines.
+// The toml crate doesn't keep track of positions, so the lines are found
+// by looking for the `[[exercises]]` headers and the keys following them.
+struct Lines<'a> {
+    lines: Vec<&'a str>,
+    // The index of the header line of every entry
+    headers: Vec<usize>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(toml_str: &'a str) -> Self {
+        let lines: Vec<&str> = toml_str.lines().collect();
+        let headers = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.trim_start().starts_with("[[exercises]]"))
+            .map(|(i, _)| i)
+            .collect();
+        Lines { lines, headers }
+    }
+
+    // The line number of the header of the `index`th entry
+    fn entry(&self, index: usize) -> Option<usize> {
+        self.headers.get(index).map(|header| header + 1)
+    }
+
+    // The line number of the key in the `index`th entry,
+    // or of the entry itself if the key can't be found
+    fn key(&self, index: usize, key: &str) -> Option<usize> {
+        let start = *self.headers.get(index)?;
+        let end = self
+            .headers
+            .get(index + 1)
+            .copied()
+            .unwrap_or(self.lines.len());
+        (start..end)
+            .find(|&i| {
+                self.lines[i]
+                    .trim_start()
+                    .strip_prefix(key)
+                    .is_some_and(|rest| rest.trim_start().starts_with('='))
+            })
+            .map(|i| i + 1)
+            .or(Some(start + 1))
+    }
+
+    // The line number of the `[[exercises.cases]]` header of the `case`th
+    // case of the `index`th entry, or of the entry itself if it can't be found
+    fn case(&self, index: usize, case: usize) -> Option<usize> {
+        let start = *self.headers.get(index)?;
+        let end = self
+            .headers
+            .get(index + 1)
+            .copied()
+            .unwrap_or(self.lines.len());
+        (start..end)
+            .filter(|&i| {
+                self.lines[i]
+                    .trim_start()
+                    .starts_with("[[exercises.cases]]")
+            })
+            .nth(case)
+            .map(|i| i + 1)
+            .or(Some(start + 1))
+    }
+}
+
+// Check info.toml for everything that would keep the exercises from
+// working as intended. `exercises_dir` is searched for exercise files
+// that aren't referenced by any entry.
+pub fn validate(toml_str: &str, exercises_dir: &Path) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let value = match toml::from_str::<toml::Value>(toml_str) {
+        Ok(value) => value,
+        Err(e) => {
+            let line = e.line_col().map(|(line, _)| line + 1);
+            problems.push(Problem::error(line, format!("invalid TOML: {e}")));
+            return problems;
+        }
+    };
+    let entries = match value.get("exercises").and_then(|e| e.as_array()) {
+        Some(entries) => entries,
+        None => {
+            problems.push(Problem::error(
+                None,
+                "there is no `[[exercises]]` array".to_string(),
+            ));
+            return problems;
+        }
+    };
+
+    let lines = Lines::new(toml_str);
+    let mut exercises = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if let Some(mode) = entry.get("mode").and_then(|mode| mode.as_str()) {
+            if !MODES.contains(&mode) {
+                problems.push(Problem::error(
+                    lines.key(index, "mode"),
+                    format!(
+                        "unknown mode `{mode}`, expected one of {}",
+                        MODES.join(", ")
+                    ),
+                ));
+                continue;
+            }
+        }
+        match entry.clone().try_into::<Exercise>() {
+            Ok(exercise) => exercises.push((index, exercise)),
+            Err(e) => problems.push(Problem::error(
+                lines.entry(index),
+                format!("invalid exercise: {e}"),
+            )),
+        }
+    }
+
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    for (index, exercise) in &exercises {
+        let line = lines.key(*index, "name");
+        match names.get(exercise.name.as_str()) {
+            Some(first) => problems.push(Problem::error(
+                line,
+                format!(
+                    "duplicate name `{}`, it's already used on line {}",
+                    exercise.name,
+                    lines.entry(*first).unwrap_or_default()
+                ),
+            )),
+            None => {
+                names.insert(&exercise.name, *index);
+            }
+        }
+    }
+
+    for (index, exercise) in &exercises {
+        problems.extend(validate_exercise(exercise, *index, &lines, &names));
+    }
+
+    let referenced: HashSet<&Path> = exercises
+        .iter()
+        .flat_map(|(_, exercise)| [Some(&exercise.path), exercise.grader.as_ref()])
+        .flatten()
+        .map(PathBuf::as_path)
+        .collect();
+    // The files of Cargo exercises belong to their package
+    let packages: Vec<&Path> = exercises
+        .iter()
+        .filter(|(_, exercise)| matches!(exercise.mode, Mode::Cargo))
+        .map(|(_, exercise)| exercise.path.as_path())
+        .collect();
+    for path in exercise_files(exercises_dir) {
+        if !referenced.contains(path.as_path())
+            && !packages.iter().any(|package| path.starts_with(package))
+        {
+            problems.push(Problem::warning(
+                None,
+                format!("{} isn't referenced by any exercise", path.display()),
+            ));
+        }
+    }
+
+    problems
+}
+
+// The checks that only concern a single exercise
+fn validate_exercise(
+    exercise: &Exercise,
+    index: usize,
+    lines: &Lines,
+    names: &HashMap<&str, usize>,
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if let Mode::Cargo = exercise.mode {
+        if !exercise.path.join("Cargo.toml").is_file() {
+            problems.push(Problem::error(
+                lines.key(index, "path"),
+                format!(
+                    "`{}` is a Cargo exercise, but {} has no Cargo.toml",
+                    exercise.name,
+                    exercise.path.display()
+                ),
+            ));
+        }
+    } else if !exercise.path.is_file() {
+        problems.push(Problem::error(
+            lines.key(index, "path"),
+            format!("{} doesn't exist", exercise.path.display()),
+        ));
+    }
+
+    let empty_hint = match &exercise.hint {
+        Hint::Single(hint) => hint.trim().is_empty(),
+        Hint::Levels(hints) => hints.is_empty() || hints.iter().any(|h| h.trim().is_empty()),
+    };
+    if empty_hint {
+        problems.push(Problem::warning(
+            lines.key(index, "hint"),
+            format!("`{}` has an empty hint", exercise.name),
+        ));
+    }
+
+    for required in &exercise.requires {
+        if !names.contains_key(required.as_str()) {
+            problems.push(Problem::error(
+                lines.key(index, "requires"),
+                format!(
+                    "`{}` requires `{required}`, which isn't an exercise",
+                    exercise.name
+                ),
+            ));
+        }
+    }
+
+    // The manifest of a BuildScript exercise is generated, only its build script has to exist
+    if let Mode::BuildScript = exercise.mode {
+        if !exercise.build_script().is_file() {
+            let dir = exercise.path.parent().unwrap_or_else(|| Path::new("."));
+            problems.push(Problem::error(
+                lines.key(index, "mode"),
+                format!(
+                    "`{}` uses a build script, but {} has no build.rs",
+                    exercise.name,
+                    dir.display()
+                ),
+            ));
+        }
+    }
+
+    if let Some(grader) = &exercise.grader {
+        let line = lines.key(index, "grader");
+        if !matches!(exercise.mode, Mode::Test) {
+            problems.push(Problem::error(
+                line,
+                format!(
+                    "`{}` has grader tests, but only test exercises can have them",
+                    exercise.name
+                ),
+            ));
+        }
+        if !grader.is_file() {
+            problems.push(Problem::error(
+                line,
+                format!("{} doesn't exist", grader.display()),
+            ));
+        }
+    }
+
+    let mut outputs = Vec::new();
+    if let Some(expected) = &exercise.expected_output {
+        let line = lines.key(index, "expected_output");
+        if !exercise.cases.is_empty() {
+            problems.push(Problem::warning(
+                line,
+                format!(
+                    "`{}` has cases, so its `expected_output` is ignored",
+                    exercise.name
+                ),
+            ));
+        }
+        outputs.push((line, format!("`{}`", exercise.name), expected));
+    }
+    for (number, case) in exercise.cases.iter().enumerate() {
+        outputs.push((
+            lines.case(index, number),
+            format!("case {} of `{}`", number + 1, exercise.name),
+            &case.expected_output,
+        ));
+    }
+    if !outputs.is_empty() && !matches!(exercise.mode, Mode::Compile) {
+        problems.push(Problem::error(
+            outputs[0].0,
+            format!(
+                "`{}` has an expected output, but only compile exercises print one",
+                exercise.name
+            ),
+        ));
+    }
+    for (line, what, expected) in outputs {
+        match expected.text() {
+            Ok(text) if expected.matching() == OutputMatch::Regex => {
+                if let Err(e) = Regex::new(&text) {
+                    problems.push(Problem::error(
+                        line,
+                        format!("the expected output of {what} isn't a valid regex: {e}"),
+                    ));
+                }
+            }
+            Ok(_) => {}
+            Err(e) => problems.push(Problem::error(
+                line,
+                format!("the expected output of {what} can't be read: {e}"),
+            )),
+        }
+    }
+    problems
+}
+
+// The source files that can be exercises, leaving out modules, build
+// scripts, hidden directories like `.rustlings` and whatever Cargo built
+fn exercise_files(exercises_dir: &Path) -> Vec<PathBuf> {
+    let pattern = exercises_dir.join("**").join("*.rs");
+    let options = MatchOptions {
+        require_literal_leading_dot: true,
+        ..MatchOptions::new()
+    };
+    let Ok(paths) = glob_with(&pattern.to_string_lossy(), options) else {
+        return Vec::new();
+    };
+    paths
+        .filter_map(Result::ok)
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default();
+            name != "mod.rs"
+                && name != "build.rs"
+                && !path
+                    .components()
+                    .any(|c| c == Component::Normal("target".as_ref()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DIR: &str = "tests/fixture/state";
+
+    fn problems(toml_str: &str) -> Vec<String> {
+        validate(toml_str, Path::new(DIR))
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let problems = problems("[[exercises]]\nname = \"a\nmode = \"compile\"\n");
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("info.toml:2: error: invalid TOML"));
+    }
+
+    #[test]
+    fn test_problems_have_line_numbers() {
+        let toml_str = r#"
+[[exercises]]
+name = "finished_exercise"
+path = "tests/fixture/state/finished_exercise.rs"
+mode = "compile"
+hint = "Some hint"
+
+[[exercises]]
+name = "finished_exercise"
+path = "tests/fixture/state/missing.rs"
+mode = "compile"
+hint = ""
+requires = ["unknown"]
+
+[[exercises]]
+name = "pending_exercise"
+path = "tests/fixture/state/pending_exercise.rs"
+mode = "run"
+hint = "Another hint"
+"#;
+        assert_eq!(
+            problems(toml_str),
+            [
+                "info.toml:18: error: unknown mode `run`, expected one of compile, test, clippy, buildscript, cargo",
+                "info.toml:9: error: duplicate name `finished_exercise`, it's already used on line 2",
+                "info.toml:10: error: tests/fixture/state/missing.rs doesn't exist",
+                "info.toml:12: warning: `finished_exercise` has an empty hint",
+                "info.toml:13: error: `finished_exercise` requires `unknown`, which isn't an exercise",
+                "info.toml: warning: tests/fixture/state/pending_exercise.rs isn't referenced by any exercise",
+                "info.toml: warning: tests/fixture/state/pending_test_exercise.rs isn't referenced by any exercise",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_script() {
+        let toml_str = |path: &str| {
+            format!("[[exercises]]\nname = \"tests8\"\npath = \"{path}\"\nmode = \"buildscript\"\nhint = \"Some hint\"\n")
+        };
+        // The exercises don't need a Cargo.toml of their own
+        let found = validate(
+            &toml_str("exercises/tests/tests8.rs"),
+            Path::new("exercises"),
+        );
+        assert!(found
+            .iter()
+            .all(|problem| !problem.to_string().contains("build")));
+        assert_eq!(
+            problems(&toml_str("tests/fixture/state/finished_exercise.rs"))[0],
+            "info.toml:4: error: `tests8` uses a build script, but tests/fixture/state has no build.rs"
+        );
+    }
+
+    #[test]
+    fn test_expected_output() {
+        let toml_str = r#"
+[[exercises]]
+name = "finished_exercise"
+path = "tests/fixture/state/finished_exercise.rs"
+mode = "compile"
+hint = "Some hint"
+expected_output = { file = "tests/fixture/state/missing.out" }
+
+[[exercises]]
+name = "pending_exercise"
+path = "tests/fixture/state/pending_exercise.rs"
+mode = "compile"
+hint = "Another hint"
+expected_output = { text = "Hello (", match = "regex" }
+
+[[exercises]]
+name = "pending_test_exercise"
+path = "tests/fixture/state/pending_test_exercise.rs"
+mode = "test"
+hint = "Another hint"
+expected_output = "Hello"
+"#;
+        let problems = problems(toml_str);
+        assert_eq!(problems.len(), 3);
+        assert!(problems[0].starts_with(
+            "info.toml:7: error: the expected output of `finished_exercise` can't be read"
+        ));
+        assert!(problems[1].starts_with(
+            "info.toml:14: error: the expected output of `pending_exercise` isn't a valid regex"
+        ));
+        assert_eq!(
+            problems[2],
+            "info.toml:21: error: `pending_test_exercise` has an expected output, but only compile exercises print one"
+        );
+    }
+
+    #[test]
+    fn test_cases() {
+        let toml_str = r#"
+[[exercises]]
+name = "finished_exercise"
+path = "tests/fixture/state/finished_exercise.rs"
+mode = "compile"
+hint = "Some hint"
+expected_output = "Hello"
+
+[[exercises.cases]]
+input = "1"
+expected_output = "1"
+
+[[exercises.cases]]
+input = "2"
+expected_output = { text = "(", match = "regex" }
+"#;
+        let problems: Vec<String> = problems(toml_str)
+            .into_iter()
+            .filter(|problem| !problem.contains("isn't referenced"))
+            .collect();
+        assert_eq!(problems.len(), 2);
+        assert_eq!(
+            problems[0],
+            "info.toml:7: warning: `finished_exercise` has cases, so its `expected_output` is ignored"
+        );
+        assert!(problems[1].starts_with(
+            "info.toml:13: error: the expected output of case 2 of `finished_exercise` isn't a valid regex"
+        ));
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let problems = problems("[[exercises]]\nname = \"a\"\nmode = \"compile\"\n");
+        assert!(problems[0].starts_with("info.toml:1: error: invalid exercise: missing field"));
+    }
+}
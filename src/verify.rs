@@ -1,4 +1,12 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::cache;
+use crate::diagnostics::Diagnostic;
+use crate::exercise::{
+    CompiledExercise, Exercise, ExerciseOutput, ExpectedOutput, Mode, OutputMatch, Stage, State,
+};
+use crate::history;
+use crate::libtest;
+use crate::message::{self, Event};
+use crate::state;
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::env;
@@ -8,12 +16,19 @@ use std::env;
 // Any such failures will be reported to the end user.
 // If the Exercise being verified is a test, the verbose boolean
 // determines whether or not the test harness outputs are displayed.
+// Exercises that are done and passed before without changing since
+// are skipped, unless force is set to true.
 pub fn verify<'a>(
     exercises: impl IntoIterator<Item = &'a Exercise>,
     progress: (usize, usize),
     verbose: bool,
     success_hints: bool,
+    force: bool,
 ) -> Result<(), &'a Exercise> {
+    if message::is_json() {
+        return verify_with_events(exercises, progress, force);
+    }
+
     let (num_done, total) = progress;
     let bar = ProgressBar::new(total as u64);
     let mut percentage = num_done as f32 / total as f32 * 100.0;
@@ -25,12 +40,20 @@ pub fn verify<'a>(
     bar.set_message(format!("({:.1} %)", percentage));
 
     for exercise in exercises {
-        let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-            Mode::Compile => compile_and_run_interactively(exercise, success_hints),
-            Mode::Clippy => compile_only(exercise, success_hints),
-            Mode::BuildScript => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-
+        let compile_result = if !force && is_cached(exercise) {
+            Ok(true)
+        } else {
+            let compile_result = match exercise.mode {
+                Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
+                Mode::Compile => compile_and_run_interactively(exercise, success_hints),
+                Mode::Clippy => compile_only(exercise, success_hints),
+                Mode::BuildScript => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
+                Mode::Cargo => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
+            };
+            state::record(exercise, compile_result.is_ok());
+            cache::record(exercise, compile_result.is_ok());
+            history::record(exercise, compile_result.is_ok());
+            compile_result
         };
         if !compile_result.unwrap_or(false) {
             return Err(exercise);
@@ -42,6 +65,93 @@ pub fn verify<'a>(
     Ok(())
 }
 
+// Verify the exercises the same way as `verify`, but report
+// what happens as JSON events instead of printing it
+fn verify_with_events<'a>(
+    exercises: impl IntoIterator<Item = &'a Exercise>,
+    progress: (usize, usize),
+    force: bool,
+) -> Result<(), &'a Exercise> {
+    let (mut num_done, total) = progress;
+    for exercise in exercises {
+        let result = if !force && is_cached(exercise) {
+            message::emit(&Event::Passed {
+                exercise: &exercise.name,
+                done: true,
+                cached: true,
+                stdout: String::new(),
+            });
+            Ok(true)
+        } else {
+            let run_binary = !matches!(exercise.mode, Mode::Clippy);
+            let result = check_with_events(exercise, run_binary);
+            state::record(exercise, result.is_ok());
+            cache::record(exercise, result.is_ok());
+            history::record(exercise, result.is_ok());
+            result
+        };
+        if result != Ok(true) {
+            return Err(exercise);
+        }
+        num_done += 1;
+        message::emit(&Event::Progress {
+            done: num_done,
+            total,
+        });
+    }
+    Ok(())
+}
+
+// Compile the given Exercise and run it unless `run_binary` is false,
+// emitting an event for every step.
+// Returns whether the exercise is done once it passed.
+pub fn check_with_events(exercise: &Exercise, run_binary: bool) -> Result<bool, ()> {
+    message::emit(&Event::started(exercise));
+    let compilation = exercise
+        .compile()
+        .map_err(|output| message::emit(&Event::failed(exercise, &output)))?;
+    message::emit(&Event::Compiled {
+        exercise: &exercise.name,
+    });
+
+    let stdout = if run_binary {
+        let output = compilation
+            .run()
+            .map_err(|output| message::emit(&Event::failed(exercise, &output)))?;
+        console::strip_ansi_codes(&output.stdout).to_string()
+    } else {
+        String::new()
+    };
+    let done = exercise.state() == State::Done;
+    message::emit(&Event::Passed {
+        exercise: &exercise.name,
+        done,
+        cached: false,
+        stdout,
+    });
+    Ok(done)
+}
+
+// Compile the given Exercise and run it unless it's a clippy exercise, the
+// same way `verify` does, but capture the output instead of printing it.
+// The result is recorded like `verify` records it.
+pub fn check_quietly(exercise: &Exercise) -> Result<ExerciseOutput, ExerciseOutput> {
+    let result = exercise.compile().and_then(|compilation| match exercise.mode {
+        Mode::Clippy => Ok(ExerciseOutput::empty(Stage::Clippy)),
+        _ => compilation.run(),
+    });
+    state::record(exercise, result.is_ok());
+    cache::record(exercise, result.is_ok());
+    history::record(exercise, result.is_ok());
+    result
+}
+
+// Whether the exercise is done and can be skipped because it
+// passed before and neither it nor the toolchain changed since
+fn is_cached(exercise: &Exercise) -> bool {
+    exercise.looks_done() && cache::is_cached(exercise)
+}
+
 enum RunMode {
     Interactive,
     NonInteractive,
@@ -79,15 +189,33 @@ fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Re
 
     let output = match result {
         Ok(output) => output,
+        Err(output) if !output.cases.is_empty() => {
+            warn_failed_cases(exercise, &output);
+            return Err(());
+        }
+        Err(output) if output.stage == Stage::Output => {
+            warn_unexpected_output(exercise, &output);
+            return Err(());
+        }
         Err(output) => {
-            warn!("Ran {} with errors", exercise);
+            if output.timed_out {
+                warn_timed_out(exercise);
+            } else {
+                warn!("Ran {} with errors", exercise);
+            }
             println!("{}", output.stdout);
             println!("{}", output.stderr);
             return Err(());
         }
     };
 
-    Ok(prompt_for_completion(exercise, Some(output.stdout), success_hints))
+    // Exercises with cases printed something different for every one of them
+    let prompt_output = if output.cases.is_empty() {
+        Some(output.stdout)
+    } else {
+        None
+    };
+    Ok(prompt_for_completion(exercise, prompt_output, success_hints))
 }
 
 // Compile the given Exercise as a test harness and display
@@ -109,25 +237,106 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
             if let RunMode::Interactive = run_mode {
                 Ok(prompt_for_completion(exercise, None, success_hints))
             } else {
+                print_test_summary(&output);
                 Ok(true)
             }
         }
         Err(output) => {
-            warn!(
-                "Testing of {} failed! Please try again. Here's the output:",
-                exercise
-            );
+            if output.timed_out {
+                warn_timed_out(exercise);
+            } else {
+                warn!(
+                    "Testing of {} failed! Please try again. Here's the output:",
+                    exercise
+                );
+            }
             println!("{}", output.stdout);
+            print_test_summary(&output);
             Err(())
         }
     }
 }
 
+// Sum up how many tests pass and which ones fail, which is easy
+// to lose track of in the output of the test harness
+fn print_test_summary(output: &ExerciseOutput) {
+    let tests = output.tests();
+    if tests.is_empty() {
+        return;
+    }
+    println!("{}", style(libtest::summary(&tests)).bold());
+    for name in libtest::failed(&tests) {
+        println!("  {} {name}", style("✗").red());
+    }
+}
+
+// Let the user know that the compiled exercise was stopped for running too long
+pub fn warn_timed_out(exercise: &Exercise) {
+    warn!("{} timed out!", exercise);
+    println!(
+        "It didn't finish within {} seconds, so it was stopped. Look out for infinite loops or deadlocks!",
+        exercise.timeout().as_secs()
+    );
+}
+
+// Let the user know that the compiled exercise didn't print the expected output,
+// showing how the output differs from it
+pub fn warn_unexpected_output(exercise: &Exercise, output: &ExerciseOutput) {
+    warn!("{} didn't print the expected output!", exercise);
+    if let Some(expected) = &exercise.expected_output {
+        print_output_mismatch(expected, output);
+    }
+}
+
+// Let the user know which of the cases of the exercise it failed, and how
+pub fn warn_failed_cases(exercise: &Exercise, output: &ExerciseOutput) {
+    warn!("{} failed some of its cases!", exercise);
+    for (number, (case, outcome)) in exercise.cases.iter().zip(&output.cases).enumerate() {
+        if outcome.passed {
+            println!("{} Case {} passed", style("✓").green(), number + 1);
+            continue;
+        }
+        println!("{} Case {} failed, with the input", style("✗").red(), number + 1);
+        println!("{}", separator());
+        println!("{}", case.input.trim_end());
+        println!("{}", separator());
+        let output = &outcome.output;
+        if output.timed_out {
+            println!("It didn't finish within {} seconds.", exercise.timeout().as_secs());
+        } else if output.stage == Stage::Output {
+            print_output_mismatch(&case.expected_output, output);
+        } else {
+            println!("It ran with errors:");
+            println!("{}", output.stdout);
+            println!("{}", output.stderr);
+        }
+    }
+}
+
+// Show how the output differs from the expected one
+fn print_output_mismatch(expected: &ExpectedOutput, output: &ExerciseOutput) {
+    let matching = expected.matching();
+    match expected.text() {
+        Ok(pattern) if matching == OutputMatch::Regex => {
+            println!("The output should match the regular expression\n{pattern}\n");
+            println!("Here's what it printed instead:\n{}", output.stdout);
+        }
+        Ok(text) => {
+            println!("Lines starting with - were expected, lines starting with + were printed instead:");
+            history::print_text_diff(
+                &matching.normalize(&text),
+                &matching.normalize(&output.stdout),
+            );
+        }
+        Err(_) => println!("{}", output.stderr),
+    }
+}
+
 // Compile the given Exercise and return an object with information
 // about the state of the compilation
-fn compile<'a, 'b>(
+fn compile<'a>(
     exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
+    progress_bar: &ProgressBar,
 ) -> Result<CompiledExercise<'a>, ()> {
     let compilation_result = exercise.compile();
 
@@ -139,12 +348,85 @@ fn compile<'a, 'b>(
                 "Compiling of {} failed! Please try again. Here's the output:",
                 exercise
             );
-            println!("{}", output.stderr);
+            print_compile_errors(&output);
             Err(())
         }
     }
 }
 
+// Summarize the messages of a failed compilation, falling back
+// to the compiler's own output if there is nothing to summarize
+pub fn print_compile_errors(output: &ExerciseOutput) {
+    let diagnostics: Vec<_> = output
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_relevant())
+        .collect();
+    if diagnostics.is_empty() {
+        println!("{}", output.stderr);
+        return;
+    }
+
+    for diagnostic in diagnostics {
+        print_diagnostic(diagnostic);
+    }
+}
+
+// Print a diagnostic like "error[E0381] at variables3.rs:8:20: used binding `x` isn't initialized",
+// followed by the line it points to and the compiler's suggestion
+fn print_diagnostic(diagnostic: &Diagnostic) {
+    let Some(span) = &diagnostic.span else {
+        return;
+    };
+    let level = match &diagnostic.code {
+        Some(code) => format!("{}[{}]", diagnostic.level, code),
+        None => diagnostic.level.clone(),
+    };
+    let level = if diagnostic.level == "error" {
+        style(level).red().bold()
+    } else {
+        style(level).yellow().bold()
+    };
+    println!(
+        "{} at {}: {}",
+        level,
+        style(format!("{}:{}:{}", span.file, span.line, span.column)).bold(),
+        diagnostic.message
+    );
+
+    let gutter = " ".repeat(span.line.to_string().len());
+    if let Some(source) = &span.source {
+        println!(
+            "{} {}  {}",
+            style(span.line).blue().bold(),
+            style("|").blue(),
+            source
+        );
+        if let Some((start, end)) = span.highlight {
+            let marker = format!(
+                "{}{} {}",
+                " ".repeat(start.saturating_sub(1)),
+                "^".repeat(end.saturating_sub(start).max(1)),
+                span.label.as_deref().unwrap_or_default()
+            );
+            let marker = marker.trim_end();
+            println!("{} {}  {}", gutter, style("|").blue(), style(marker).red());
+        }
+    }
+    if let Some(suggestion) = &diagnostic.suggestion {
+        println!("{} {} help: {}", gutter, style("=").blue(), suggestion);
+    }
+    if let Some(explanation) = diagnostic.explanation() {
+        println!(
+            "{} {} learn more: {}",
+            gutter,
+            style("=").blue(),
+            explanation
+        );
+    }
+    println!();
+}
+
 fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, success_hints: bool) -> bool {
     let context = match exercise.state() {
         State::Done => return true,
@@ -155,6 +437,7 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
         Mode::Test => success!("Successfully tested {}!", exercise),
         Mode::Clippy => success!("Successfully compiled {}!", exercise),
         Mode::BuildScript => success!("Successfully compiled {}!", exercise),
+        Mode::Cargo => success!("Successfully tested {}!", exercise),
     }
 
     let no_emoji = env::var("NO_EMOJI").is_ok();
@@ -170,6 +453,7 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
         Mode::Test => "The code is compiling, and the tests pass!",
         Mode::Clippy => clippy_success_msg,
         Mode::BuildScript => "Build script works!",
+        Mode::Cargo => "The crate is compiling, and the tests pass!",
     };
     println!();
     if no_emoji {
@@ -0,0 +1,121 @@
+use notify::{PollWatcher, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer_opt, Config, DebounceEventResult};
+use std::any::Any;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+// The directory the exercises are in, which is watched for changes
+const EXERCISES_DIR: &str = "./exercises";
+// How often the files are polled at most with `--poll`, so that a short
+// `--debounce` doesn't have the watcher scan the exercises nonstop
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// How the exercises are watched
+pub struct WatchOptions {
+    // How long the files must stay unchanged before they're reported
+    pub debounce: Duration,
+    // Whether to poll the files for changes instead of relying on the
+    // notifications of the OS, which network filesystems often don't send
+    pub poll: bool,
+}
+
+// Watches the exercises directory for files that changed
+pub struct ExerciseWatcher {
+    // The debouncer of whichever backend is used, which keeps watching
+    // as long as it's kept around
+    _debouncer: Box<dyn Any>,
+    // Every batch of changes, with the canonical paths of the files
+    // that changed, or the error the watcher ran into
+    pub changes: Receiver<notify::Result<Vec<PathBuf>>>,
+}
+
+impl ExerciseWatcher {
+    pub fn new(options: &WatchOptions) -> notify::Result<Self> {
+        let (tx, changes) = channel();
+        let handler = move |result: DebounceEventResult| {
+            let result = result.map(|events| {
+                let mut paths: Vec<PathBuf> = events
+                    .into_iter()
+                    .filter(|event| is_changed_file(&event.path))
+                    .filter_map(|event| event.path.canonicalize().ok())
+                    .collect();
+                paths.sort();
+                paths.dedup();
+                paths
+            });
+            if !result.as_ref().is_ok_and(|paths| paths.is_empty()) {
+                let _ = tx.send(result);
+            }
+        };
+
+        let notify_config = notify::Config::default()
+            .with_poll_interval(options.debounce.max(MIN_POLL_INTERVAL));
+        let config = Config::default()
+            .with_timeout(options.debounce)
+            .with_notify_config(notify_config);
+        let debouncer: Box<dyn Any> = if options.poll {
+            let mut debouncer = new_debouncer_opt::<_, PollWatcher>(config, handler)?;
+            debouncer
+                .watcher()
+                .watch(Path::new(EXERCISES_DIR), RecursiveMode::Recursive)?;
+            Box::new(debouncer)
+        } else {
+            let mut debouncer = new_debouncer_opt::<_, RecommendedWatcher>(config, handler)?;
+            debouncer
+                .watcher()
+                .watch(Path::new(EXERCISES_DIR), RecursiveMode::Recursive)?;
+            Box::new(debouncer)
+        };
+        Ok(ExerciseWatcher {
+            _debouncer: debouncer,
+            changes,
+        })
+    }
+}
+
+// Whether the path is a file that still exists once the changes settled.
+// This leaves out the temporary files of editors that save by renaming.
+// Any file can be part of an exercise, like a file it `include_str!`s.
+fn is_changed_file(path: &Path) -> bool {
+    !is_ignored(path) && path.is_file()
+}
+
+// Whether the path is a hidden file, like the swap files of editors,
+// or something Cargo wrote to the target directory of an exercise
+fn is_ignored(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    hidden
+        || path
+            .components()
+            .any(|c| c == Component::Normal("target".as_ref()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_ignored() {
+        assert!(!is_ignored(Path::new("./exercises/intro/intro1.rs")));
+        assert!(is_ignored(Path::new("./exercises/intro/.intro1.rs")));
+        assert!(is_ignored(Path::new(
+            "./exercises/clippy/target/debug/build/out.rs"
+        )));
+    }
+
+    #[test]
+    fn test_is_changed_file() {
+        assert!(is_changed_file(Path::new(
+            "tests/fixture/state/pending_exercise.rs"
+        )));
+        assert!(is_changed_file(Path::new(
+            "tests/fixture/deps/greeting.txt"
+        )));
+        assert!(!is_changed_file(Path::new(
+            "tests/fixture/state/missing.rs"
+        )));
+    }
+}
@@ -1,12 +1,16 @@
 use assert_cmd::prelude::*;
+use std::env;
 use std::process::Command;
 
+// Grades the exercises, checking their tests against the checkout of the
+// original exercises in `RUSTLINGS_PRISTINE` when it's set, like the workflow
+// does. Without it, the tests of the exercises aren't checked for changes.
 #[test]
 fn cicvverify() {
-    Command::cargo_bin("rustlings")
-        .unwrap()
-        .args(&["--nocapture", "cicvverify"]) 
-        // .current_dir("exercises")
-        .assert()
-        .success();
+    let mut cmd = Command::cargo_bin("rustlings").unwrap();
+    cmd.args(["--nocapture", "cicvverify"]);
+    if let Some(pristine) = env::var_os("RUSTLINGS_PRISTINE") {
+        cmd.arg("--pristine").arg(pristine);
+    }
+    cmd.assert().success();
 }
@@ -0,0 +1,5 @@
+// I AM NOT DONE
+
+pub fn double(n: u32) -> u32 {
+    n + 2
+}
@@ -0,0 +1,4 @@
+#[test]
+fn double() {
+    assert_eq!(broken::double(3), 6);
+}
@@ -0,0 +1,3 @@
+pub fn squared(n: u32) -> u32 {
+    n * n
+}
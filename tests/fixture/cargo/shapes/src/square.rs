@@ -0,0 +1,17 @@
+pub struct Square(pub u32);
+
+impl Square {
+    pub fn area(&self) -> u32 {
+        helper::squared(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_area() {
+        assert_eq!(Square(1).area(), 1);
+    }
+}
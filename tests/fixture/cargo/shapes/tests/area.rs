@@ -0,0 +1,7 @@
+use shapes::Square;
+
+#[test]
+fn area() {
+    println!("The area of a 3x3 square is {}", Square(3).area());
+    assert_eq!(Square(3).area(), 9);
+}
@@ -0,0 +1,8 @@
+mod shared;
+mod util;
+
+fn main() {
+    println!("{}", include_str!("greeting.txt"));
+    util::helper();
+    shared::nested::helper();
+}
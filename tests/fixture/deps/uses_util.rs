@@ -0,0 +1,7 @@
+#[path = "util.rs"]
+mod util;
+
+#[test]
+fn test_helper() {
+    util::helper();
+}
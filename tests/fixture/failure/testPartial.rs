@@ -0,0 +1,23 @@
+fn square(n: i32) -> i32 {
+    n * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squares_zero() {
+        assert_eq!(square(0), 0);
+    }
+
+    #[test]
+    fn squares_two() {
+        assert_eq!(square(2), 4);
+    }
+
+    #[test]
+    fn squares_three() {
+        assert_eq!(square(3), 9);
+    }
+}
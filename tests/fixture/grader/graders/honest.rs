@@ -0,0 +1,6 @@
+use super::*;
+
+#[test]
+fn adds_negative_numbers() {
+    ::core::assert_eq!(add(-1, -2), -3);
+}
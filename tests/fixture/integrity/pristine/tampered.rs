@@ -0,0 +1,18 @@
+fn multiply(a: i32, b: i32) -> i32 {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiplies() {
+        assert_eq!(multiply(3, 4), 12);
+    }
+
+    #[test]
+    fn multiplies_by_zero() {
+        assert_eq!(multiply(3, 0), 0);
+    }
+}
@@ -0,0 +1,13 @@
+fn multiply(a: i32, b: i32) -> i32 {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiplies() {
+        assert_eq!(multiply(3, 4), 12);
+    }
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let elapsed = 40 + 2;
+    println!("Took {elapsed}ms");
+}
@@ -0,0 +1,10 @@
+use std::io::{self, BufRead};
+
+fn main() {
+    for line in io::stdin().lock().lines() {
+        let line = line.unwrap();
+        // Only the first word is shouted
+        let (first, rest) = line.split_once(' ').unwrap_or((&line, ""));
+        println!("{} {}", first.to_uppercase(), rest);
+    }
+}
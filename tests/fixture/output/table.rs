@@ -0,0 +1,5 @@
+fn main() {
+    for n in 1..=3 {
+        println!("{n} x {n} = {}", n * n);
+    }
+}
@@ -3,6 +3,8 @@ use glob::glob;
 use predicates::boolean::PredicateBooleanExt;
 use std::fs::File;
 use std::io::Read;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[test]
@@ -22,74 +24,93 @@ fn fails_when_in_wrong_dir() {
 
 #[test]
 fn verify_all_success() {
+    let dir = copy_fixture("success", "rustlings_verify_all_success");
     Command::cargo_bin("rustlings")
         .unwrap()
         .arg("verify")
-        .current_dir("tests/fixture/success")
+        .current_dir(&dir)
         .assert()
         .success();
 }
 
 #[test]
 fn verify_fails_if_some_fails() {
+    let dir = copy_fixture("failure", "rustlings_verify_fails_if_some_fails");
     Command::cargo_bin("rustlings")
         .unwrap()
         .arg("verify")
-        .current_dir("tests/fixture/failure")
+        .current_dir(&dir)
         .assert()
         .code(1);
 }
 
 #[test]
 fn run_single_compile_success() {
+    let dir = copy_fixture("success", "rustlings_run_single_compile_success");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compSuccess"])
-        .current_dir("tests/fixture/success/")
+        .args(["run", "compSuccess"])
+        .current_dir(&dir)
         .assert()
         .success();
 }
 
 #[test]
 fn run_single_compile_failure() {
+    let dir = copy_fixture("failure", "rustlings_run_single_compile_failure");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compFailure"])
-        .current_dir("tests/fixture/failure/")
+        .args(["run", "compFailure"])
+        .current_dir(&dir)
         .assert()
         .code(1);
 }
 
 #[test]
 fn run_single_test_success() {
+    let dir = copy_fixture("success", "rustlings_run_single_test_success");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
-        .current_dir("tests/fixture/success/")
+        .args(["run", "testSuccess"])
+        .current_dir(&dir)
         .assert()
         .success();
 }
 
 #[test]
 fn run_single_test_failure() {
+    let dir = copy_fixture("failure", "rustlings_run_single_test_failure");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testFailure"])
-        .current_dir("tests/fixture/failure/")
+        .args(["run", "testFailure"])
+        .current_dir(&dir)
         .assert()
         .code(1);
 }
 
 #[test]
 fn run_single_test_not_passed() {
+    let dir = copy_fixture("failure", "rustlings_run_single_test_not_passed");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testNotPassed.rs"])
-        .current_dir("tests/fixture/failure/")
+        .args(["run", "testNotPassed.rs"])
+        .current_dir(&dir)
         .assert()
         .code(1);
 }
 
+#[test]
+fn run_single_exercise_timeout() {
+    let dir = copy_fixture("failure", "rustlings_run_single_exercise_timeout");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "timeout"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("timed out"));
+}
+
 #[test]
 fn run_single_test_no_filename() {
     Command::cargo_bin("rustlings")
@@ -102,41 +123,125 @@ fn run_single_test_no_filename() {
 
 #[test]
 fn run_single_test_no_exercise() {
+    let dir = copy_fixture("failure", "rustlings_run_single_test_no_exercise");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compNoExercise.rs"])
-        .current_dir("tests/fixture/failure")
+        .args(["run", "compNoExercise.rs"])
+        .current_dir(&dir)
         .assert()
         .code(1);
 }
 
 #[test]
 fn reset_single_exercise() {
+    let dir = copy_fixture("state", "rustlings_reset");
+    let exercise = dir.join("pending_exercise.rs");
+    let original = std::fs::read_to_string(&exercise).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .current_dir(&dir)
+        .assert()
+        .success();
+    std::fs::write(&exercise, "fn main() {}\n").unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "pending_exercise"])
+        .current_dir(&dir)
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains(
+            "Your previous attempts were saved to",
+        ));
+    assert_eq!(std::fs::read_to_string(&exercise).unwrap(), original);
+    let backups = glob(&format!(
+        "{}/.rustlings/backup/*/pending_exercise.rs",
+        dir.display()
+    ))
+    .unwrap()
+    .count();
+    assert_eq!(backups, 1);
+}
+
+#[test]
+fn reset_all_exercises() {
+    let dir = copy_fixture("state", "rustlings_reset_all");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .current_dir(&dir)
+        .assert()
+        .success();
+    std::fs::write(dir.join("pending_exercise.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(dir.join("finished_exercise.rs"), "fn main() {}\n").unwrap();
+
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["reset", "intro1"])
+        .args(["reset", "--all"])
+        .current_dir(&dir)
         .assert()
         .code(0);
+    let pending = std::fs::read_to_string(dir.join("pending_exercise.rs")).unwrap();
+    assert!(pending.contains("I AM NOT DONE"));
+}
+
+#[test]
+fn reset_exercises_without_originals() {
+    let dir = copy_fixture("state", "rustlings_reset_without_originals");
+    // Only the exercises around when `init` ran have an original version
+    let info = std::fs::read_to_string(dir.join("info.toml")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"pending_exercise\"\npath = \"pending_exercise.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .current_dir(&dir)
+        .assert()
+        .success();
+    std::fs::write(dir.join("info.toml"), info).unwrap();
+    std::fs::write(dir.join("pending_exercise.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(dir.join("finished_exercise.rs"), "fn main() {}\n").unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "--all"])
+        .current_dir(&dir)
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("Reset pending_exercise.rs").and(
+            predicates::str::contains("There is no original version of finished_exercise.rs"),
+        ));
+    let pending = std::fs::read_to_string(dir.join("pending_exercise.rs")).unwrap();
+    assert!(pending.contains("I AM NOT DONE"));
+    let finished = std::fs::read_to_string(dir.join("finished_exercise.rs")).unwrap();
+    assert_eq!(finished, "fn main() {}\n");
 }
 
 #[test]
 fn reset_no_exercise() {
+    let dir = copy_fixture("state", "rustlings_reset_no_exercise");
     Command::cargo_bin("rustlings")
         .unwrap()
         .arg("reset")
+        .current_dir(&dir)
         .assert()
         .code(1)
-        .stderr(predicates::str::contains(
-            "positional arguments not provided",
+        .stdout(predicates::str::contains(
+            "Which exercises should be reset?",
         ));
 }
 
 #[test]
 fn get_hint_for_single_test() {
+    let dir = copy_fixture("failure", "rustlings_get_hint_for_single_test");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["hint", "testFailure"])
-        .current_dir("tests/fixture/failure")
+        .args(["hint", "testFailure"])
+        .current_dir(&dir)
         .assert()
         .code(0)
         .stdout("Hello!\n");
@@ -169,10 +274,11 @@ fn all_exercises_require_confirmation() {
 
 #[test]
 fn run_compile_exercise_does_not_prompt() {
+    let dir = copy_fixture("state", "rustlings_run_compile_exercise_does_not_prompt");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_exercise"])
-        .current_dir("tests/fixture/state")
+        .args(["run", "pending_exercise"])
+        .current_dir(&dir)
         .assert()
         .code(0)
         .stdout(predicates::str::contains("I AM NOT DONE").not());
@@ -180,10 +286,11 @@ fn run_compile_exercise_does_not_prompt() {
 
 #[test]
 fn run_test_exercise_does_not_prompt() {
+    let dir = copy_fixture("state", "rustlings_run_test_exercise_does_not_prompt");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_test_exercise"])
-        .current_dir("tests/fixture/state")
+        .args(["run", "pending_test_exercise"])
+        .current_dir(&dir)
         .assert()
         .code(0)
         .stdout(predicates::str::contains("I AM NOT DONE").not());
@@ -191,10 +298,11 @@ fn run_test_exercise_does_not_prompt() {
 
 #[test]
 fn run_single_test_success_with_output() {
+    let dir = copy_fixture("success", "rustlings_run_single_test_success_with_output");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["--nocapture", "run", "testSuccess"])
-        .current_dir("tests/fixture/success/")
+        .args(["--nocapture", "run", "testSuccess"])
+        .current_dir(&dir)
         .assert()
         .code(0)
         .stdout(predicates::str::contains("THIS TEST TOO SHALL PASS"));
@@ -202,10 +310,14 @@ fn run_single_test_success_with_output() {
 
 #[test]
 fn run_single_test_success_without_output() {
+    let dir = copy_fixture(
+        "success",
+        "rustlings_run_single_test_success_without_output",
+    );
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
-        .current_dir("tests/fixture/success/")
+        .args(["run", "testSuccess"])
+        .current_dir(&dir)
         .assert()
         .code(0)
         .stdout(predicates::str::contains("THIS TEST TOO SHALL PASS").not());
@@ -213,20 +325,28 @@ fn run_single_test_success_without_output() {
 
 #[test]
 fn run_rustlings_list() {
+    let dir = copy_fixture("success", "rustlings_run_rustlings_list");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
-        .current_dir("tests/fixture/success")
+        .args(["list"])
+        .current_dir(&dir)
         .assert()
         .success();
 }
 
 #[test]
 fn run_rustlings_list_no_pending() {
+    let dir = copy_fixture("success", "rustlings_run_rustlings_list_no_pending");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
-        .current_dir("tests/fixture/success")
+        .arg("verify")
+        .current_dir(&dir)
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list"])
+        .current_dir(&dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Pending").not());
@@ -234,10 +354,15 @@ fn run_rustlings_list_no_pending() {
 
 #[test]
 fn run_rustlings_list_both_done_and_pending() {
+    let dir = copy_fixture(
+        "state",
+        "rustlings_run_rustlings_list_both_done_and_pending",
+    );
+    run_finished_exercise(&dir);
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
-        .current_dir("tests/fixture/state")
+        .args(["list"])
+        .current_dir(&dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").and(predicates::str::contains("Pending")));
@@ -245,22 +370,707 @@ fn run_rustlings_list_both_done_and_pending() {
 
 #[test]
 fn run_rustlings_list_without_pending() {
+    let dir = copy_fixture("state", "rustlings_run_rustlings_list_without_pending");
+    run_finished_exercise(&dir);
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--solved"])
-        .current_dir("tests/fixture/state")
+        .args(["list", "--solved"])
+        .current_dir(&dir)
         .assert()
         .success()
-        .stdout(predicates::str::contains("Pending").not());
+        .stdout(
+            predicates::str::contains("finished_exercise")
+                .and(predicates::str::contains("Pending").not()),
+        );
 }
 
 #[test]
 fn run_rustlings_list_without_done() {
+    let dir = copy_fixture("state", "rustlings_run_rustlings_list_without_done");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--unsolved"])
-        .current_dir("tests/fixture/state")
+        .args(["list", "--unsolved"])
+        .current_dir(&dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+#[test]
+fn run_rustlings_list_by_metadata() {
+    let dir = copy_fixture("state", "rustlings_run_rustlings_list_by_metadata");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args([
+            "list",
+            "--names",
+            "--tag",
+            "ownership",
+            "--max-difficulty",
+            "1",
+        ])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("pending_test_exercise")
+                .and(predicates::str::contains("pending_exercise\n").not()),
+        );
+}
+
+#[test]
+fn run_rustlings_list_sorted_by_time() {
+    let dir = copy_fixture("state", "rustlings_run_rustlings_list_sorted_by_time");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names", "--sort", "time"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "pending_test_exercise\npending_exercise\nfinished_exercise\n",
+        ));
+}
+
+#[test]
+fn cicvverify_writes_report_in_format() {
+    let dir = copy_fixture("success", "rustlings_cicvverify_writes_report_in_format");
+    let report = dir.join("nested").join("report.xml");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--format", "junit", "--output"])
+        .arg(&report)
+        .current_dir(&dir)
+        .assert()
+        .success();
+    let mut contents = String::new();
+    File::open(&report)
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert!(contents.contains("<testsuite name=\"rustlings\" tests=\"2\" failures=\"0\""));
+}
+
+#[test]
+fn cicvverify_fails_if_some_fails() {
+    let dir = copy_fixture("failure", "rustlings_cicvverify_fails_if_some_fails");
+    let report = dir.join("report.tap");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--format", "tap", "--output"])
+        .arg(&report)
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn cicvverify_reports_weighted_score() {
+    let dir = copy_fixture("success", "rustlings_cicvverify_reports_weighted_score");
+    let report = dir.join("report.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--output"])
+        .arg(&report)
+        .current_dir(&dir)
+        .assert()
+        .success();
+    let mut contents = String::new();
+    File::open(&report)
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert!(contents.contains("\"total_points\": 4"));
+    assert!(contents.contains("\"earned_points\": 4"));
+    assert!(contents.contains("\"total_time\": "));
+    assert!(contents.contains("\"total_time_ms\": "));
+}
+
+#[test]
+fn run_single_compile_failure_json() {
+    let dir = copy_fixture("failure", "rustlings_run_single_compile_failure_json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--message-format", "json", "run", "compFailure"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains(r#"{"event":"started","exercise":"compFailure""#)
+                .and(predicates::str::contains(r#""event":"failed""#))
+                .and(predicates::str::contains(r#""stage":"compile""#))
+                .and(predicates::str::contains(
+                    r#""diagnostics":[{"level":"error""#,
+                )),
+        );
+}
+
+#[test]
+fn run_rustlings_list_json() {
+    let dir = copy_fixture("state", "rustlings_run_rustlings_list_json");
+    run_finished_exercise(&dir);
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--message-format", "json", "list"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains(
+                r#"{"event":"exercise","name":"finished_exercise","path":"finished_exercise.rs","mode":"compile","done":true,"difficulty":null,"tags":[],"requires":[],"estimated_minutes":null}"#,
+            )
+            .and(predicates::str::contains(r#"{"event":"progress","done":1,"total":3}"#)),
+        );
+}
+
+#[test]
+fn get_hint_json() {
+    let dir = copy_fixture("failure", "rustlings_get_hint_json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--message-format", "json", "hint", "testFailure"])
+        .current_dir(&dir)
+        .assert()
+        .code(0)
+        .stdout("{\"event\":\"hint\",\"exercise\":\"testFailure\",\"hint\":\"Hello!\",\"level\":1,\"levels\":1}\n");
+}
+
+#[test]
+fn edited_exercise_is_no_longer_solved() {
+    let dir = std::env::temp_dir().join("rustlings_progress_state");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(
+        "tests/fixture/state/finished_exercise.rs",
+        dir.join("finished_exercise.rs"),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"finished_exercise\"\npath = \"finished_exercise.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "finished_exercise"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    assert!(dir.join(".rustlings/state.json").exists());
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--solved", "--names"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("finished_exercise"));
+
+    std::fs::write(
+        dir.join("finished_exercise.rs"),
+        "fn main() {\n    // edited\n}\n",
+    )
+    .unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--solved", "--names"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("finished_exercise").not());
+}
+
+#[test]
+fn verify_skips_unchanged_exercises() {
+    let dir = copy_fixture("success", "rustlings_verify_cache");
+    let verify = |args: &[&str]| {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["--message-format", "json", "verify"])
+            .args(args)
+            .current_dir(&dir)
+            .assert()
+            .success()
+    };
+
+    verify(&[]).stdout(predicates::str::contains(r#""cached":true"#).not());
+    verify(&[]).stdout(predicates::str::contains(r#""cached":false"#).not());
+    verify(&["--force"]).stdout(predicates::str::contains(r#""cached":true"#).not());
+}
+
+#[test]
+fn get_hints_one_level_at_a_time() {
+    let dir = copy_fixture("failure", "rustlings_hint_levels");
+    let hint = || {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["hint", "compFailure"])
+            .current_dir(&dir)
+            .assert()
+            .success()
+    };
+
+    hint().stdout(
+        predicates::str::contains("Hint 1/2:\nLook at the end")
+            .and(predicates::str::contains("needs a pattern").not()),
+    );
+    hint().stdout(predicates::str::contains(
+        "Hint 2/2:\nA `let` needs a pattern",
+    ));
+
+    let report = dir.join("report.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--output"])
+        .arg(&report)
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+    let contents = std::fs::read_to_string(report).unwrap();
+    assert!(contents.contains("\"total_hints_used\": 2"));
+}
+
+#[test]
+fn validate_fixture() {
+    let dir = copy_fixture("success", "rustlings_validate_fixture");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("validate")
+        .current_dir(&dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn invalid_info_toml_is_reported_with_line_numbers() {
+    let dir = copy_fixture("success", "rustlings_validate");
+    let mut info = std::fs::read_to_string(dir.join("info.toml")).unwrap();
+    info += "\n[[exercises]]\nname = \"compSuccess\"\npath = \"missing.rs\"\nmode = \"compile\"\nhint = \"\"\n";
+    std::fs::write(dir.join("info.toml"), info).unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("validate")
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("error: duplicate name `compSuccess`")
+                .and(predicates::str::contains("error: missing.rs doesn't exist")),
+        );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compSuccess"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::is_match(r"info\.toml:\d+: error: duplicate name").unwrap());
+}
+
+#[test]
+fn history_and_diff_of_attempts() {
+    let dir = copy_fixture("state", "rustlings_history");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .current_dir(&dir)
+        .assert()
+        .success();
+    let verify = || {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .arg("verify")
+            .current_dir(&dir)
+            .assert()
+            .code(1)
+    };
+    verify();
+    let exercise = dir.join("pending_exercise.rs");
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    std::fs::write(
+        &exercise,
+        source.replace("fn main() {", "fn main() {\n    let x = ;"),
+    )
+    .unwrap();
+    verify();
+    verify();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["history", "pending_exercise"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("#1")
+                .and(predicates::str::contains("#2"))
+                .and(predicates::str::contains("#3").not()),
+        );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["diff", "pending_exercise"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("+    let x = ;"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["diff", "pending_exercise", "1"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("the same as the original"));
+}
+
+#[test]
+fn history_and_diff_of_cargo_exercise() {
+    let dir = copy_fixture("cargo", "rustlings_history_of_cargo_exercise");
+    let rustlings = |args: &[&str]| {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(args)
+            .current_dir(&dir)
+            .assert()
+    };
+    rustlings(&["init"]).success();
+    // `broken` fails after `shapes` passes
+    rustlings(&["verify"]).code(1);
+    let square = dir.join("shapes/src/square.rs");
+    let source = std::fs::read_to_string(&square).unwrap();
+    std::fs::write(
+        &square,
+        source.replace("helper::squared(self.0)", "self.0 * self.0"),
+    )
+    .unwrap();
+    rustlings(&["verify"]).code(1);
+
+    rustlings(&["history", "shapes"])
+        .success()
+        .stdout(predicates::str::contains("#2").and(predicates::str::contains("#3").not()));
+    rustlings(&["diff", "shapes"]).success().stdout(
+        predicates::str::contains("shapes/src/square.rs")
+            .and(predicates::str::contains(
+                "-        helper::squared(self.0)",
+            ))
+            .and(predicates::str::contains("+        self.0 * self.0"))
+            .and(predicates::str::contains("shapes/src/lib.rs").not()),
+    );
+}
+
+#[test]
+fn run_cargo_exercise_with_output() {
+    let dir = copy_fixture("cargo", "rustlings_run_cargo_exercise_with_output");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--nocapture", "run", "shapes"])
+        .current_dir(&dir)
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("The area of a 3x3 square is 9"));
+}
+
+#[test]
+fn run_failing_cargo_exercise() {
+    let dir = copy_fixture("cargo", "rustlings_run_failing_cargo_exercise");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "broken"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("test double ... FAILED"));
+}
+
+#[test]
+fn run_exercises_with_expected_output() {
+    let dir = copy_fixture("output", "rustlings_run_exercises_with_expected_output");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "table"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "elapsed"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_exercise_with_unexpected_output() {
+    let dir = copy_fixture("output", "rustlings_run_exercise_with_unexpected_output");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "wrongGreeting"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("-Hello, world!")
+                .and(predicates::str::contains("+Hello, World!")),
+        );
+}
+
+#[test]
+fn run_exercise_with_cases() {
+    let dir = copy_fixture("output", "rustlings_run_exercise_with_cases");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sum"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("All 2 cases passed."));
+}
+
+#[test]
+fn run_exercise_failing_a_case() {
+    let dir = copy_fixture("output", "rustlings_run_exercise_failing_a_case");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "shout"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("Case 1 passed")
+                .and(predicates::str::contains("Case 2 failed"))
+                .and(predicates::str::contains("+HELLO, world!")),
+        );
+}
+
+#[test]
+fn cicvverify_runs_grader_tests() {
+    let dir = copy_fixture("grader", "rustlings_cicvverify_runs_grader_tests");
+    copy_dir(Path::new("tests/fixture/grader"), &dir.join("pristine"));
+    // Neither a grader that passes anything nor more points count,
+    // since both come from the original exercises
+    std::fs::write(
+        dir.join("graders/tampered.rs"),
+        "#[test]\nfn multiplies() {}\n",
+    )
+    .unwrap();
+    let info = std::fs::read_to_string(dir.join("info.toml")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        info.replace("mode = \"test\"", "mode = \"test\"\npoints = 100"),
+    )
+    .unwrap();
+    let report = dir.join("report.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--pristine", "pristine", "--output"])
+        .arg(&report)
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+    let mut contents = String::new();
+    File::open(&report)
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert!(contents.contains("\"stage\": \"grader\""));
+    assert!(contents.contains("test grader::adds_negative_numbers ... ok"));
+    assert!(contents.contains("test grader::multiplies ... FAILED"));
+    assert!(contents.contains("\"earned_points\": 1"));
+}
+
+#[test]
+fn cicvverify_skips_graders_without_original_exercises() {
+    let dir = copy_fixture(
+        "grader",
+        "rustlings_cicvverify_skips_graders_without_original_exercises",
+    );
+    let report = dir.join("report.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--output"])
+        .arg(&report)
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("their graders won't run"));
+    let report = read_report(&report);
+    for exercise in report["exercises"].as_array().unwrap() {
+        assert!(exercise["grader"].is_null());
+    }
+}
+
+#[test]
+fn cicvverify_flags_modified_tests() {
+    let dir = copy_fixture("integrity", "rustlings_cicvverify_flags_modified_tests");
+    let report = dir.join("report.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--pristine", "pristine", "--output"])
+        .arg(&report)
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+    let report = read_report(&report);
+    let exercise = |name: &str| {
+        report["exercises"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|exercise| exercise["name"] == name)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(exercise("untouched")["tests_modified"], false);
+    assert_eq!(
+        exercise("tampered")["modifications"],
+        serde_json::json!([
+            "the assertions of the test `multiplies` were changed",
+            "`#[ignore]` was added to the test `multiplies_by_zero`",
+        ])
+    );
+    assert_eq!(
+        exercise("hidden")["modifications"],
+        serde_json::json!([
+            "`#[cfg(any())]` was added to the module of the test `multiplies_by_zero`",
+            "only 1 of the 2 tests ran",
+        ])
+    );
+    assert_eq!(exercise("hidden")["result"], true);
+    // Leaving out every test fails the exercise, rather than just flagging it
+    assert_eq!(exercise("untested")["result"], false);
+    assert_eq!(exercise("untested")["stage"], "test");
+}
+
+#[test]
+fn cicvverify_without_original_exercises() {
+    let dir = copy_fixture(
+        "integrity",
+        "rustlings_cicvverify_without_original_exercises",
+    );
+    let report = dir.join("report.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--pristine", "missing", "--output"])
+        .arg(&report)
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "There are no original exercises in missing, so the tests of the exercises won't be checked for changes and their graders won't run",
+        ));
+    let report = read_report(&report);
+    for exercise in report["exercises"].as_array().unwrap() {
+        assert_eq!(exercise["tests_modified"], false);
+    }
+}
+
+#[test]
+fn run_test_exercise_shows_failing_tests() {
+    let dir = copy_fixture("failure", "rustlings_run_test_exercise_shows_failing_tests");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "testPartial"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("2/3 tests passing")
+                .and(predicates::str::contains("✗ tests::squares_three")),
+        );
+}
+
+#[test]
+fn cicvverify_reports_test_results() {
+    let dir = copy_fixture("failure", "rustlings_cicvverify_reports_test_results");
+    let report = dir.join("report.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--output"])
+        .arg(&report)
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+    let report = read_report(&report);
+    let tests = report["exercises"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|exercise| exercise["tests"].as_array().unwrap())
+        .collect::<Vec<_>>();
+    let outcomes = tests
+        .iter()
+        .map(|test| {
+            (
+                test["name"].as_str().unwrap(),
+                test["outcome"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert!(outcomes.contains(&("tests::squares_two", "passed")));
+    assert!(outcomes.contains(&("tests::squares_three", "failed")));
+    assert!(tests.iter().all(|test| test["duration_ms"].is_u64()));
+}
+
+fn read_report(path: &Path) -> serde_json::Value {
+    serde_json::from_reader(File::open(path).unwrap()).unwrap()
+}
+
+// Verify the only finished exercise of the state fixture, since exercises
+// only count as done once they're verified
+fn run_finished_exercise(dir: &Path) {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "finished_exercise"])
+        .current_dir(dir)
+        .assert()
+        .success();
+}
+
+// A copy of a fixture in a fresh temporary directory, so that the state
+// rustlings writes in .rustlings and whatever the exercises write stay out of
+// the fixtures and don't leak into other tests. It's removed when dropped.
+struct Fixture(PathBuf);
+
+impl Deref for Fixture {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for Fixture {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn copy_fixture(fixture: &str, name: &str) -> Fixture {
+    let dir = std::env::temp_dir().join(format!("{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    copy_dir(&Path::new("tests/fixture").join(fixture), &dir);
+    Fixture(dir)
+}
+
+// Copy the directory recursively, leaving out hidden files and what cargo wrote
+fn copy_dir(from: &Path, to: &Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap();
+        let name_str = name.to_string_lossy();
+        if name_str.starts_with('.') || name_str == "target" || name_str == "Cargo.lock" {
+            continue;
+        }
+        if path.is_dir() {
+            copy_dir(&path, &to.join(name));
+        } else {
+            std::fs::copy(&path, to.join(name)).unwrap();
+        }
+    }
+}